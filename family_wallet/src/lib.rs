@@ -1,7 +1,8 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, token::TokenClient, Address,
-    Env, Map, Symbol, Vec,
+    contract, contractclient, contracterror, contractimpl, contracttype, panic_with_error,
+    symbol_short, token::TokenClient, xdr::ToXdr, Address, Bytes, BytesN, Env, Map, String, Symbol,
+    Vec,
 };
 
 // Storage TTL constants for active data
@@ -15,6 +16,55 @@ const ARCHIVE_BUMP_AMOUNT: u32 = 2592000;
 // Signature expiration time (24 hours in seconds)
 const SIGNATURE_EXPIRATION: u64 = 86400;
 
+// Spending window lengths in seconds
+const DAY_SECONDS: u64 = 86400;
+const WEEK_SECONDS: u64 = 7 * DAY_SECONDS;
+const MONTH_SECONDS: u64 = 30 * DAY_SECONDS;
+const YEAR_SECONDS: u64 = 365 * DAY_SECONDS;
+
+// Per-wallet members and policy state that `force_reinitialize` drops.
+// Every new instance key belongs here unless it is deliberately kept
+// across a reset (audit, archives, upgrade/TTL settings, id counters,
+// deposit totals) or rewritten by `write_initial_state`.
+const WALLET_STATE_KEYS: [Symbol; 36] = [
+    symbol_short!("ADMINS"),
+    symbol_short!("ALLOW"),
+    symbol_short!("APPR_TIER"),
+    symbol_short!("AUTO_ENR"),
+    symbol_short!("BLK_INC"),
+    symbol_short!("CAT_LIM"),
+    symbol_short!("DCL_ALRT"),
+    symbol_short!("DELEGATES"),
+    symbol_short!("DISPUTE"),
+    symbol_short!("HIST_RET"),
+    symbol_short!("LIM_BND"),
+    symbol_short!("LIM_HIST"),
+    symbol_short!("MEM_DEF"),
+    symbol_short!("META"),
+    symbol_short!("MS_REG"),
+    symbol_short!("ORACLE"),
+    symbol_short!("OWN_DLY"),
+    symbol_short!("PAUSED"),
+    symbol_short!("PAUSE_ADM"),
+    symbol_short!("PAUSE_WIN"),
+    symbol_short!("PEND_OWN"),
+    symbol_short!("RCPT_APR"),
+    symbol_short!("RCPT_KNWN"),
+    symbol_short!("RCPT_PEND"),
+    symbol_short!("RCPT_WIN"),
+    symbol_short!("REQ_RSN"),
+    symbol_short!("ROLE_EXP"),
+    symbol_short!("SPND_BKT"),
+    symbol_short!("SPND_HIST"),
+    symbol_short!("TOK_LIM"),
+    symbol_short!("UNIQ_NAME"),
+    symbol_short!("WAL_CAP"),
+    symbol_short!("WAL_RATE"),
+    symbol_short!("WD_DELAY"),
+    symbol_short!("WD_REQS"),
+    symbol_short!("W_NAME"),
+];
+
 #[contracttype]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
@@ -35,6 +85,20 @@ pub enum FamilyRole {
     Admin = 2,
     Member = 3,
     Viewer = 4,
+    /// Shared-custody party that can only freeze limit changes with
+    /// `flag_dispute`; ranks below Viewer for every other check.
+    Guardian = 5,
+}
+
+/// Window over which a member's `spending_limit` applies to recorded spends.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum LimitPeriod {
+    Daily = 1,
+    Weekly = 2,
+    Monthly = 3,
+    Lifetime = 4,
 }
 
 #[contracttype]
@@ -45,6 +109,14 @@ pub struct MultiSigConfig {
     pub spending_limit: i128,
 }
 
+/// Withdrawals of at least `min_amount` need `required_approvals` signatures.
+#[contracttype]
+#[derive(Clone)]
+pub struct ApprovalTier {
+    pub min_amount: i128,
+    pub required_approvals: u32,
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub struct PendingTransaction {
@@ -72,9 +144,226 @@ pub enum TransactionData {
 pub struct FamilyMember {
     pub address: Address,
     pub role: FamilyRole,
-    /// Per-transaction cap in stroops. 0 = unlimited.
+    /// Per-transaction cap in stroops, and the per-period cap for
+    /// recorded spends. 0 = unlimited.
     pub spending_limit: i128,
+    /// Ledger timestamp of the member's first add; re-adding an existing
+    /// address keeps it.
     pub added_at: u64,
+    /// Frozen members cannot spend until unfrozen by an Owner or Admin.
+    pub frozen: bool,
+    pub limit_period: LimitPeriod,
+    /// Ledger timestamp at which the current spending window started.
+    pub period_start: u64,
+    pub spent_in_period: i128,
+    /// Total recorded spend over the member's lifetime.
+    pub spent: i128,
+    /// Inactive members keep their settings but cannot spend.
+    pub active: bool,
+    /// Distinct withdrawal recipients allowed per spending window.
+    /// 0 = unlimited.
+    pub max_recipients: u32,
+    /// Internal balance credited by claimed allowances and treasury
+    /// allocations.
+    pub balance: i128,
+    /// Designated approver whose signature this member's multisig
+    /// proposals need before they execute.
+    pub approver: Option<Address>,
+    /// When set, each `allocate_to_member` deposit clears the member's
+    /// `spent`, `spent_in_period` and `tx_count`, so the full limit is
+    /// available again.
+    pub auto_topup_on_deposit: bool,
+    /// Ledger timestamp after which the member can no longer spend.
+    /// `None` never expires.
+    pub limit_expires_at: Option<u64>,
+    /// Spends recorded in the current spending window.
+    pub tx_count: u32,
+    /// Spends allowed per spending window. 0 = unlimited.
+    pub max_tx_count: u32,
+    /// Birth date used by `get_effective_limit`. `None` disables age
+    /// scaling.
+    pub birth_timestamp: Option<u64>,
+    /// Added to `spending_limit` per full year of age.
+    pub limit_per_year_of_age: i128,
+    /// Display name set by `update_member_name`. `None` until named.
+    pub name: Option<String>,
+}
+
+/// Wallet-wide spending budget per 30-day month. Unused budget carries into
+/// the next month up to `max_rollover` (0 = no rollover).
+#[contracttype]
+#[derive(Clone)]
+pub struct WalletMonthlyCap {
+    pub limit: i128,
+    pub max_rollover: i128,
+    pub period_start: u64,
+    pub spent: i128,
+    pub carried: i128,
+}
+
+/// Wallet-wide cap on the number of spends per fixed window of
+/// `window_secs`, counted across all members.
+#[contracttype]
+#[derive(Clone)]
+pub struct WalletRateLimit {
+    pub max_spends: u32,
+    pub window_secs: u64,
+    pub window_start: u64,
+    pub count: u32,
+}
+
+/// Owner-configured range that Member/Viewer spending limits must fall
+/// in. A bound of 0 is unset; with a `max_limit` set, the unlimited
+/// limit 0 is out of range.
+#[contracttype]
+#[derive(Clone)]
+pub struct LimitBounds {
+    pub min_limit: i128,
+    pub max_limit: i128,
+}
+
+/// Role and spending limit `quick_add_member` assigns, set by
+/// `set_defaults`.
+#[contracttype]
+#[derive(Clone)]
+pub struct MemberDefaults {
+    pub role: FamilyRole,
+    pub spending_limit: i128,
+}
+
+/// Instance TTL policy applied by every mutating call; defaults to
+/// `INSTANCE_LIFETIME_THRESHOLD`/`INSTANCE_BUMP_AMOUNT`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TtlConfig {
+    pub threshold: u32,
+    pub bump_amount: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct LimitChange {
+    pub old_limit: i128,
+    pub new_limit: i128,
+    pub timestamp: u64,
+    /// Reason given to `update_limit_with_reason`, if any.
+    pub memo: Option<String>,
+}
+
+/// What a `MemberAuditEntry` records.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MemberAuditKind {
+    Created,
+    LimitChanged,
+    Frozen,
+    Unfrozen,
+    Spend,
+}
+
+/// One event in a member's consolidated audit trail. `value` is the limit
+/// for `Created` and `LimitChanged` and the amount for `Spend`; `previous`
+/// is the old limit for `LimitChanged`. Unused fields are `0`.
+#[contracttype]
+#[derive(Clone)]
+pub struct MemberAuditEntry {
+    pub kind: MemberAuditKind,
+    pub timestamp: u64,
+    pub value: i128,
+    pub previous: i128,
+}
+
+/// Outcome of `evaluate_spend`.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SpendCheckResult {
+    Allowed,
+    OverLimit,
+    MemberNotFound,
+    MemberInactive,
+    InvalidAmount,
+    LimitExpired,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct SpendRecord {
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+/// Dashboard header returned by `get_summary`.
+#[contracttype]
+#[derive(Clone)]
+pub struct WalletSummary {
+    pub owner: Address,
+    /// Same as `get_member_count`: owner records are not counted.
+    pub member_count: u32,
+    pub paused: bool,
+    /// Same as `get_total_limit`.
+    pub total_limit: i128,
+}
+
+/// The settings a member actually operates under once role rules, role
+/// expiry, the current spending window and wallet-level pauses are applied
+/// on top of their stored record.
+#[contracttype]
+#[derive(Clone)]
+pub struct ResolvedConfig {
+    pub address: Address,
+    pub role: FamilyRole,
+    pub role_expires_at: Option<u64>,
+    pub role_expired: bool,
+    /// Owner/Admin roles and a stored limit of 0 both resolve to unlimited.
+    pub unlimited: bool,
+    /// The stored limit, before the unlimited rules are applied.
+    pub spending_limit: i128,
+    pub limit_period: LimitPeriod,
+    /// Start of the window containing the current ledger timestamp.
+    pub period_start: u64,
+    pub remaining_limit: i128,
+    pub max_recipients: u32,
+    /// False when the member is frozen, inactive, role-expired or past their
+    /// `limit_expires_at`, or the wallet is paused or inside a scheduled
+    /// pause window.
+    pub can_spend: bool,
+}
+
+/// Recurring allowance: `amount` accrues every `interval` seconds since
+/// `last_claim`, and a single claim pays out at most `cap` (0 = no cap).
+#[contracttype]
+#[derive(Clone)]
+pub struct AllowanceConfig {
+    pub amount: i128,
+    pub interval: u64,
+    pub cap: i128,
+    pub last_claim: u64,
+}
+
+/// Role and limit given to unknown recipients that `transfer_balance`
+/// enrolls as members.
+#[contracttype]
+#[derive(Clone)]
+pub struct AutoEnrollConfig {
+    pub role: FamilyRole,
+    pub spending_limit: i128,
+}
+
+/// Distinct recipients a member has withdrawn to in the window starting
+/// at `period_start`.
+#[contracttype]
+#[derive(Clone)]
+pub struct RecipientWindow {
+    pub period_start: u64,
+    pub recipients: Vec<Address>,
+}
+
+/// Spending is blocked while `start <= now < end`.
+#[contracttype]
+#[derive(Clone)]
+pub struct PauseWindow {
+    pub start: u64,
+    pub end: u64,
 }
 
 #[contracttype]
@@ -85,6 +374,14 @@ pub struct EmergencyConfig {
     pub min_balance: i128,
 }
 
+#[contracttype]
+#[derive(Clone)]
+pub enum FamilyWalletEvent {
+    Initialized,
+    MembersCleared,
+    TtlExtended,
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub enum EmergencyEvent {
@@ -92,6 +389,74 @@ pub enum EmergencyEvent {
     ModeOff,
     TransferInit,
     TransferExec,
+    Withdrawal,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum OwnershipEvent {
+    TransferProposed,
+    TransferConfirmed,
+    Transferred,
+    Renounced,
+    CoOwnerAdded,
+    CoOwnerRemoved,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct PendingOwnership {
+    pub new_owner: Address,
+    pub proposed_at: u64,
+    /// Set by `confirm_transfer` when owner confirmation is enabled.
+    pub confirmed: bool,
+}
+
+/// Kind of operation reported by `get_pending_operations`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PendingOpKind {
+    OwnershipTransfer,
+    Transaction,
+    WithdrawalRequest,
+}
+
+/// One entry in the consolidated pending-operations view.
+///
+/// `id` is the `tx_id` for transactions, the request id for withdrawal
+/// requests and `0` for the ownership transfer. `subject` is the proposed
+/// owner, the transaction proposer or the requesting member.
+/// `effective_at` is the earliest time the operation can complete and
+/// `expires_at` is `0` when it never lapses.
+#[contracttype]
+#[derive(Clone)]
+pub struct PendingOp {
+    pub kind: PendingOpKind,
+    pub id: u64,
+    pub subject: Address,
+    pub created_at: u64,
+    pub effective_at: u64,
+    pub expires_at: u64,
+}
+
+/// A member spend queued by `request_withdrawal`, executable from
+/// `executable_at` (request time plus the delay then configured).
+#[contracttype]
+#[derive(Clone)]
+pub struct WithdrawalRequest {
+    pub id: u64,
+    pub member: Address,
+    pub amount: i128,
+    pub requested_at: u64,
+    pub executable_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct OwnershipTransferredEvent {
+    pub previous_owner: Address,
+    pub new_owner: Address,
+    pub timestamp: u64,
 }
 
 #[contracttype]
@@ -110,6 +475,54 @@ pub struct SpendingLimitUpdatedEvent {
     pub old_limit: i128,
     pub new_limit: i128,
     pub timestamp: u64,
+    pub memo: Option<String>,
+}
+
+/// Published under `(deposit, received, from)` by `record_deposit`.
+#[contracttype]
+#[derive(Clone)]
+pub struct DepositReceivedEvent {
+    pub from: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+/// Published under `(spent, reset)` by `reset_spent`.
+#[contracttype]
+#[derive(Clone)]
+pub struct SpentResetEvent {
+    pub member: Address,
+    pub previous_spent: i128,
+    pub timestamp: u64,
+}
+
+/// Published under `(family, TtlExtended)` whenever the instance TTL is
+/// extended, so keepers can see which wallets were refreshed.
+#[contracttype]
+#[derive(Clone)]
+pub struct TtlExtendedEvent {
+    pub bump_amount: u32,
+    pub ledger: u32,
+}
+
+/// Published under `(alert, owner)` when decline alerts are enabled and a
+/// member's `spend` is declined. `reason` is the `Error` code.
+#[contracttype]
+#[derive(Clone)]
+pub struct OwnerAlertEvent {
+    pub member: Address,
+    pub amount: i128,
+    pub reason: u32,
+    pub timestamp: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct MemberStatusChangedEvent {
+    pub member: Address,
+    pub active: bool,
+    pub timestamp: u64,
 }
 
 #[contracttype]
@@ -139,11 +552,25 @@ pub struct AccessAuditEntry {
     pub target: Option<Address>,
     pub timestamp: u64,
     pub success: bool,
+    pub reason: Option<String>,
 }
 
-const CONTRACT_VERSION: u32 = 1;
+/// Interface version reported by `get_version` until an upgrade admin
+/// records a newer one. Bump on breaking storage or API changes.
+pub const CONTRACT_VERSION: u32 = 2;
 const MAX_ACCESS_AUDIT_ENTRIES: u32 = 100;
 const MAX_BATCH_MEMBERS: u32 = 30;
+const MAX_PAUSE_WINDOWS: u32 = 10;
+const MAX_TREND_PERIODS: u32 = 12;
+const MAX_LIMIT_HISTORY: u32 = 20;
+const MAX_MEMBER_METADATA_KEYS: u32 = 10;
+const MAX_MEMO_LEN: u32 = 64;
+const MAX_NAME_LEN: u32 = 32;
+/// Fixed-point scale of oracle prices: `PRICE_SCALE` is one reference unit
+/// per token unit.
+const PRICE_SCALE: i128 = 10_000_000;
+/// How long a `flag_dispute` freezes member and limit changes.
+const DISPUTE_WINDOW_SECONDS: u64 = 3 * DAY_SECONDS;
 
 #[contracttype]
 #[derive(Clone)]
@@ -152,6 +579,16 @@ pub struct BatchMemberItem {
     pub role: FamilyRole,
 }
 
+/// A new member together with their spending limit, as accepted by the
+/// strict onboarding entry points.
+#[contracttype]
+#[derive(Clone)]
+pub struct MemberSpec {
+    pub address: Address,
+    pub role: FamilyRole,
+    pub spending_limit: i128,
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub enum ArchiveEvent {
@@ -159,6 +596,15 @@ pub enum ArchiveEvent {
     ExpiredCleaned,
 }
 
+// Client trait for the reference-currency price feed
+
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracleTrait {
+    /// Reference-currency value of one unit of `token`, scaled by
+    /// `PRICE_SCALE`.
+    fn price(env: Env, token: Address) -> i128;
+}
+
 #[contract]
 pub struct FamilyWallet;
 
@@ -179,6 +625,26 @@ pub enum Error {
     MemberNotFound = 11,
     TransactionAlreadyExecuted = 12,
     InvalidSpendingLimit = 13,
+    SpendingLimitExceeded = 14,
+    MemberFrozen = 15,
+    MemberInactive = 16,
+    ReasonRequired = 17,
+    BatchTooLarge = 18,
+    AllowanceNotSet = 19,
+    InsufficientBalance = 20,
+    AlreadyInitialized = 21,
+    NotInitialized = 22,
+    WalletCapExceeded = 23,
+    MemberOverLimit = 24,
+    LimitOutOfBounds = 25,
+    WithdrawalDelayActive = 26,
+    LimitExpired = 27,
+    TxCountExceeded = 28,
+    DisputeActive = 29,
+    MetadataLimitExceeded = 30,
+    DefaultsNotSet = 31,
+    MemoTooLong = 32,
+    RecipientLimitReached = 33,
 }
 
 #[contractimpl]
@@ -186,95 +652,102 @@ impl FamilyWallet {
     pub fn init(env: Env, owner: Address, initial_members: Vec<Address>) -> bool {
         owner.require_auth();
 
-        let existing: Option<Address> = env.storage().instance().get(&symbol_short!("OWNER"));
-        if existing.is_some() {
-            panic!("Wallet already initialized");
+        if Self::was_initialized(&env) {
+            panic_with_error!(&env, Error::AlreadyInitialized);
         }
 
-        Self::extend_instance_ttl(&env);
-
-        env.storage()
-            .instance()
-            .set(&symbol_short!("OWNER"), &owner);
-
-        let mut members: Map<Address, FamilyMember> = Map::new(&env);
-        let timestamp = env.ledger().timestamp();
+        Self::write_initial_state(&env, &owner, initial_members);
+        true
+    }
 
-        members.set(
-            owner.clone(),
-            FamilyMember {
-                address: owner.clone(),
-                role: FamilyRole::Owner,
-                spending_limit: 0,
-                added_at: timestamp,
-            },
-        );
+    /// Deliberately wipe the wallet back to a freshly initialized state
+    /// owned by `owner`, the only way to run initialization twice. Root
+    /// owner only. Members, co-owners, pending operations, any active
+    /// dispute and policy settings (`WALLET_STATE_KEYS`) are dropped; the
+    /// access audit, archived transactions, upgrade and TTL settings,
+    /// `record_deposit` totals and the transaction and withdrawal id
+    /// counters are kept.
+    pub fn force_reinitialize(env: Env, owner: Address) -> Result<bool, Error> {
+        owner.require_auth();
+        if owner != Self::require_initialized(&env) {
+            return Err(Error::Unauthorized);
+        }
 
-        for member_addr in initial_members.iter() {
-            members.set(
-                member_addr.clone(),
-                FamilyMember {
-                    address: member_addr.clone(),
-                    role: FamilyRole::Member,
-                    spending_limit: 0,
-                    added_at: timestamp,
-                },
-            );
+        let next_tx: u64 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("NEXT_TX"))
+            .unwrap_or(1);
+        for key in WALLET_STATE_KEYS {
+            env.storage().instance().remove(&key);
         }
 
+        Self::write_initial_state(&env, &owner, Vec::new(&env));
         env.storage()
             .instance()
-            .set(&symbol_short!("MEMBERS"), &members);
+            .set(&symbol_short!("NEXT_TX"), &next_tx);
+        Self::append_access_audit(&env, symbol_short!("reinit"), &owner, None, true);
+        Self::update_storage_stats(&env);
 
-        let default_config = MultiSigConfig {
-            threshold: 2,
-            signers: Vec::new(&env),
-            spending_limit: 1000_0000000,
-        };
+        Ok(true)
+    }
 
-        for tx_type in [
-            TransactionType::LargeWithdrawal,
-            TransactionType::SplitConfigChange,
-            TransactionType::RoleChange,
-            TransactionType::EmergencyTransfer,
-            TransactionType::PolicyCancellation,
-        ] {
-            env.storage()
-                .instance()
-                .set(&Self::get_config_key(tx_type), &default_config.clone());
-        }
+    /// Number of times the wallet has been initialized, counting
+    /// `force_reinitialize`. `0` for a never-initialized contract.
+    pub fn get_init_generation(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&symbol_short!("INIT_GEN"))
+            .unwrap_or(0)
+    }
 
-        env.storage().instance().set(
-            &symbol_short!("PEND_TXS"),
-            &Map::<u64, PendingTransaction>::new(&env),
-        );
+    /// `init` plus strict onboarding of `members` in one call. Every entry
+    /// is validated like `add_members_batch` (and may not be the owner)
+    /// before anything is written, so one bad entry leaves the wallet
+    /// uninitialized.
+    pub fn initialize_with_members(
+        env: Env,
+        owner: Address,
+        members: Vec<MemberSpec>,
+    ) -> Result<bool, Error> {
+        owner.require_auth();
 
-        env.storage()
-            .instance()
-            .set(&symbol_short!("EXEC_TXS"), &Map::<u64, bool>::new(&env));
+        if Self::was_initialized(&env) {
+            return Err(Error::AlreadyInitialized);
+        }
+        if members.len() > MAX_BATCH_MEMBERS {
+            return Err(Error::BatchTooLarge);
+        }
+        let mut owner_only: Map<Address, FamilyMember> = Map::new(&env);
+        owner_only.set(
+            owner.clone(),
+            Self::new_member(owner.clone(), FamilyRole::Owner, 0, 0),
+        );
+        Self::validate_member_specs(&env, &owner_only, &members)?;
 
-        env.storage()
-            .instance()
-            .set(&symbol_short!("NEXT_TX"), &1u64);
+        Self::write_initial_state(&env, &owner, Vec::new(&env));
 
-        let em_config = EmergencyConfig {
-            max_amount: 1000_0000000,
-            cooldown: 3600,
-            min_balance: 0,
-        };
-        env.storage()
-            .instance()
-            .set(&symbol_short!("EM_CONF"), &em_config);
-        env.storage()
+        let mut members_map: Map<Address, FamilyMember> = env
+            .storage()
             .instance()
-            .set(&symbol_short!("EM_MODE"), &false);
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        Self::insert_member_specs(&env, &owner, &mut members_map, members);
         env.storage()
             .instance()
-            .set(&symbol_short!("EM_LAST"), &0u64);
+            .set(&symbol_short!("MEMBERS"), &members_map);
+        Self::update_storage_stats(&env);
 
-        true
+        Ok(true)
     }
 
+    /// Add a new member with an explicit role and spending limit.
+    ///
+    /// Fails with `InvalidRole` if `role` is `Owner` or `member_address` is
+    /// already a member or an owner/co-owner (owners are never limited
+    /// members), `Unauthorized` unless `admin` is the Owner or an
+    /// Admin, and `InvalidSpendingLimit` if `spending_limit` is negative.
+    /// `role` is a typed `FamilyRole`, so it can never be empty or unknown.
     pub fn add_member(
         env: Env,
         admin: Address,
@@ -288,18 +761,23 @@ impl FamilyWallet {
         if role == FamilyRole::Owner {
             return Err(Error::InvalidRole);
         }
-        if !Self::is_owner_or_admin(&env, &admin) {
+        if !Self::is_owner_or_admin(&env, &admin) || Self::is_renounced(&env) {
             return Err(Error::Unauthorized);
         }
+        Self::require_no_dispute(&env)?;
+        if Self::has_owner_rights(&env, &member_address) {
+            return Err(Error::InvalidRole);
+        }
         if spending_limit < 0 {
             return Err(Error::InvalidSpendingLimit);
         }
+        Self::check_limit_bounds(&env, role, spending_limit)?;
 
         let mut members: Map<Address, FamilyMember> = env
             .storage()
             .instance()
             .get(&symbol_short!("MEMBERS"))
-            .expect("Wallet not initialized");
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
 
         if members.get(member_address.clone()).is_some() {
             return Err(Error::InvalidRole);
@@ -310,19 +788,18 @@ impl FamilyWallet {
         let now = env.ledger().timestamp();
         members.set(
             member_address.clone(),
-            FamilyMember {
-                address: member_address.clone(),
-                role,
-                spending_limit,
-                added_at: now,
-            },
+            Self::new_member(member_address.clone(), role, spending_limit, now),
         );
         env.storage()
             .instance()
             .set(&symbol_short!("MEMBERS"), &members);
 
         env.events().publish(
-            (symbol_short!("added"), symbol_short!("member")),
+            (
+                symbol_short!("added"),
+                symbol_short!("member"),
+                member_address.clone(),
+            ),
             MemberAddedEvent {
                 member: member_address,
                 role,
@@ -334,1054 +811,4785 @@ impl FamilyWallet {
         Ok(true)
     }
 
+    /// `add_member` with the role and limit from `set_defaults`. Fails
+    /// with `DefaultsNotSet` until defaults are configured; the stored
+    /// defaults are re-validated, so later limit bounds still apply.
+    pub fn quick_add_member(
+        env: Env,
+        admin: Address,
+        member_address: Address,
+    ) -> Result<bool, Error> {
+        let defaults = Self::get_defaults(env.clone()).ok_or(Error::DefaultsNotSet)?;
+        Self::add_member(
+            env,
+            admin,
+            member_address,
+            defaults.role,
+            defaults.spending_limit,
+        )
+    }
+
     pub fn get_member(env: Env, member_address: Address) -> Option<FamilyMember> {
+        Self::require_initialized(&env);
         let members: Map<Address, FamilyMember> = env
             .storage()
             .instance()
             .get(&symbol_short!("MEMBERS"))
-            .expect("Wallet not initialized");
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
 
         members.get(member_address)
     }
 
-    pub fn update_spending_limit(
+    /// `get_member` for callers that must prove who is asking: only an
+    /// Owner/Admin or the member themselves may read the record.
+    pub fn get_member_private(
         env: Env,
-        caller: Address,
+        requester: Address,
         member_address: Address,
-        new_limit: i128,
-    ) -> Result<bool, Error> {
-        caller.require_auth();
-        Self::require_not_paused(&env);
-
-        if !Self::is_owner_or_admin(&env, &caller) {
+    ) -> Result<FamilyMember, Error> {
+        requester.require_auth();
+        if requester != member_address && !Self::is_owner_or_admin(&env, &requester) {
             return Err(Error::Unauthorized);
         }
-        if new_limit < 0 {
-            return Err(Error::InvalidSpendingLimit);
-        }
-
-        let mut members: Map<Address, FamilyMember> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("MEMBERS"))
-            .expect("Wallet not initialized");
-
-        let mut record = members
-            .get(member_address.clone())
-            .ok_or(Error::MemberNotFound)?;
-
-        let old_limit = record.spending_limit;
-        record.spending_limit = new_limit;
-        members.set(member_address.clone(), record);
+        Self::get_member(env, member_address).ok_or(Error::MemberNotFound)
+    }
 
-        Self::extend_instance_ttl(&env);
+    /// Whether `member_address` has a member record (the owner included).
+    /// `false` on an uninitialized wallet.
+    pub fn is_member(env: Env, member_address: Address) -> bool {
         env.storage()
             .instance()
-            .set(&symbol_short!("MEMBERS"), &members);
-
-        let now = env.ledger().timestamp();
-        env.events().publish(
-            (symbol_short!("updated"), symbol_short!("limit")),
-            SpendingLimitUpdatedEvent {
-                member: member_address,
-                old_limit,
-                new_limit,
-                timestamp: now,
-            },
-        );
+            .get::<_, Map<Address, FamilyMember>>(&symbol_short!("MEMBERS"))
+            .map(|members| members.contains_key(member_address))
+            .unwrap_or(false)
+    }
 
-        Ok(true)
+    /// `get_member` without the `Option`: non-members come back as a zeroed
+    /// `Viewer` record for `member_address` with `active = false`, so it can
+    /// never be mistaken for a member able to spend (a limit of `0` alone
+    /// would read as unlimited).
+    pub fn get_member_or_default(env: Env, member_address: Address) -> FamilyMember {
+        Self::get_member(env, member_address.clone()).unwrap_or_else(|| {
+            let mut member = Self::new_member(member_address, FamilyRole::Viewer, 0, 0);
+            member.active = false;
+            member
+        })
     }
 
-    /// Check if `caller` is allowed to spend `amount`.
-    ///
-    /// Rules (checked in order):
-    /// 1. Unknown address → false
-    /// 2. Negative amount → false
-    /// 3. Owner / Admin → always true (unlimited)
-    /// 4. Member with `spending_limit == 0` → unlimited → true
-    /// 5. Member with `spending_limit > 0` → true iff `amount <= spending_limit`
-    pub fn check_spending_limit(env: Env, caller: Address, amount: i128) -> bool {
-        if amount < 0 {
-            return false;
+    /// Set a member's spending limit. An optional `memo` (at most
+    /// `MAX_MEMO_LEN` bytes; empty means none) is recorded in the access
+    /// audit log, the limit history and the update event, and satisfies
+    /// `set_require_change_reason`.
+    pub fn update_spending_limit(
+        env: Env,
+        caller: Address,
+        member_address: Address,
+        new_limit: i128,
+        memo: Option<String>,
+    ) -> Result<bool, Error> {
+        caller.require_auth();
+        let memo = memo.filter(|memo| !memo.is_empty());
+        if memo.is_none() && Self::is_change_reason_required(&env) {
+            return Err(Error::ReasonRequired);
         }
+        Self::set_spending_limit(&env, &caller, member_address, new_limit, memo)?;
+        Ok(true)
+    }
 
-        let members: Map<Address, FamilyMember> =
-            match env.storage().instance().get(&symbol_short!("MEMBERS")) {
-                Some(m) => m,
-                None => return false,
-            };
-
-        let member = match members.get(caller) {
-            Some(m) => m,
-            None => return false,
-        };
-
-        // Owner and Admin are never restricted
-        if member.role == FamilyRole::Owner || member.role == FamilyRole::Admin {
-            return true;
+    /// `update_spending_limit` returning the limit it replaced, for one-call
+    /// "changed from X to Y" confirmations. `None` when `member_address` is
+    /// not a member; every other failure is the same error.
+    pub fn update_spending_limit_returning(
+        env: Env,
+        caller: Address,
+        member_address: Address,
+        new_limit: i128,
+    ) -> Result<Option<i128>, Error> {
+        caller.require_auth();
+        if Self::is_change_reason_required(&env) {
+            return Err(Error::ReasonRequired);
         }
-
-        // 0 means unlimited for regular members too
-        if member.spending_limit == 0 {
-            return true;
+        match Self::set_spending_limit(&env, &caller, member_address, new_limit, None) {
+            Ok(old_limit) => Ok(Some(old_limit)),
+            Err(Error::MemberNotFound) => Ok(None),
+            Err(e) => Err(e),
         }
+    }
 
-        amount <= member.spending_limit
+    /// Raise a member's limit by `delta` instead of passing the absolute
+    /// value. Returns the new limit. Unlimited members (limit `0`) are
+    /// rejected with `InvalidSpendingLimit` rather than silently capped.
+    pub fn increase_spending_limit(
+        env: Env,
+        caller: Address,
+        member_address: Address,
+        delta: i128,
+    ) -> Result<i128, Error> {
+        caller.require_auth();
+        Self::adjust_spending_limit(&env, &caller, member_address, delta, true)
     }
 
-    pub fn configure_multisig(
+    /// Lower a member's limit by `delta`. Returns the new limit. The result
+    /// must stay positive: a decrease reaching `0` (which would mean
+    /// unlimited) or below fails with `InvalidSpendingLimit`.
+    pub fn decrease_spending_limit(
         env: Env,
         caller: Address,
-        tx_type: TransactionType,
-        threshold: u32,
-        signers: Vec<Address>,
-        spending_limit: i128,
-    ) -> bool {
+        member_address: Address,
+        delta: i128,
+    ) -> Result<i128, Error> {
         caller.require_auth();
-        Self::require_not_paused(&env);
+        Self::adjust_spending_limit(&env, &caller, member_address, delta, false)
+    }
+
+    /// Move `amount` of spending limit from `from` to `to` in one call.
+    /// Owner-only. Both members must have a positive (capped) limit, and
+    /// `from` must keep a positive limit no lower than what they have
+    /// already spent in the current window.
+    pub fn transfer_limit(
+        env: Env,
+        owner: Address,
+        from: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<bool, Error> {
+        owner.require_auth();
+        if !Self::has_owner_rights(&env, &owner) {
+            return Err(Error::Unauthorized);
+        }
+        if Self::is_change_reason_required(&env) {
+            return Err(Error::ReasonRequired);
+        }
+        if amount <= 0 || from == to {
+            return Err(Error::InvalidAmount);
+        }
 
         let members: Map<Address, FamilyMember> = env
             .storage()
             .instance()
             .get(&symbol_short!("MEMBERS"))
-            .expect("Wallet not initialized");
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        let mut source = members.get(from.clone()).ok_or(Error::MemberNotFound)?;
+        let target = members.get(to.clone()).ok_or(Error::MemberNotFound)?;
+        if source.spending_limit == 0 || target.spending_limit == 0 {
+            return Err(Error::InvalidSpendingLimit);
+        }
 
-        if !Self::is_owner_or_admin_in_members(&members, &caller) {
-            panic!("Only Owner or Admin can configure multi-sig");
+        Self::roll_spend_window(&mut source, env.ledger().timestamp());
+        let new_from = source.spending_limit - amount;
+        if new_from <= 0 || new_from < source.spent_in_period {
+            return Err(Error::InvalidSpendingLimit);
         }
+        let new_to = target
+            .spending_limit
+            .checked_add(amount)
+            .ok_or(Error::InvalidSpendingLimit)?;
 
-        // Validate threshold
-        let signer_count = signers.len();
-        if threshold == 0 || threshold > signer_count {
-            panic!("Invalid threshold");
+        Self::set_spending_limit(&env, &owner, from, new_from, None)?;
+        Self::set_spending_limit(&env, &owner, to, new_to, None)?;
+        Ok(true)
+    }
+
+    /// Set every `role` member's limit to `new_limit` in one call, e.g. a
+    /// back-to-school raise for all Members. Owner only; `new_limit` must
+    /// be positive and `role` cannot be `Owner`. Each member goes through
+    /// the same checks as `update_spending_limit`, and any failure rejects
+    /// the whole update. Returns how many members were updated.
+    pub fn update_limits_by_role(
+        env: Env,
+        owner: Address,
+        role: FamilyRole,
+        new_limit: i128,
+    ) -> Result<u32, Error> {
+        owner.require_auth();
+        if !Self::has_owner_rights(&env, &owner) {
+            return Err(Error::Unauthorized);
+        }
+        if role == FamilyRole::Owner {
+            return Err(Error::InvalidRole);
+        }
+        if new_limit <= 0 {
+            return Err(Error::InvalidSpendingLimit);
+        }
+        if Self::is_change_reason_required(&env) {
+            return Err(Error::ReasonRequired);
         }
 
-        for signer in signers.iter() {
-            if members.get(signer.clone()).is_none() {
-                panic!("Signer must be a family member");
+        let members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        let mut updated = 0u32;
+        for (address, member) in members.iter() {
+            if member.role != role {
+                continue;
             }
+            Self::set_spending_limit(&env, &owner, address, new_limit, None)?;
+            updated += 1;
         }
 
-        if spending_limit < 0 {
-            panic!("Spending limit must be non-negative");
-        }
+        Ok(updated)
+    }
 
-        Self::extend_instance_ttl(&env);
+    /// Start a fresh spending window for `member_address` now, zeroing both
+    /// `spent` and `spent_in_period` (e.g. a new budget cycle or an
+    /// accounting correction). Owner-only. Returns `false` for non-members.
+    pub fn reset_spent(env: Env, owner: Address, member_address: Address) -> bool {
+        owner.require_auth();
+        if !Self::has_owner_rights(&env, &owner) {
+            panic!("Only Owner can reset spent");
+        }
 
-        let config = MultiSigConfig {
-            threshold,
-            signers,
-            spending_limit,
+        let mut members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        let mut member = match members.get(member_address.clone()) {
+            Some(member) => member,
+            None => return false,
         };
 
+        let previous_spent = member.spent;
+        let now = env.ledger().timestamp();
+        member.spent = 0;
+        member.spent_in_period = 0;
+        member.tx_count = 0;
+        member.period_start = now;
+        members.set(member_address.clone(), member);
+
+        Self::extend_instance_ttl(&env);
         env.storage()
             .instance()
-            .set(&Self::get_config_key(tx_type), &config);
+            .set(&symbol_short!("MEMBERS"), &members);
+
+        env.events().publish(
+            (
+                symbol_short!("spent"),
+                symbol_short!("reset"),
+                member_address.clone(),
+            ),
+            SpentResetEvent {
+                member: member_address,
+                previous_spent,
+                timestamp: now,
+            },
+        );
 
         true
     }
 
-    pub fn propose_transaction(
+    /// Same as `update_spending_limit`, recording a non-empty `reason` (at
+    /// most `MAX_MEMO_LEN` bytes) in the access audit log, the limit
+    /// history and the update event. Required when
+    /// `set_require_change_reason` is on.
+    pub fn update_limit_with_reason(
         env: Env,
-        proposer: Address,
-        tx_type: TransactionType,
-        data: TransactionData,
-    ) -> u64 {
-        proposer.require_auth();
-        Self::require_not_paused(&env);
-        Self::require_role_at_least(&env, &proposer, FamilyRole::Member);
-
-        if !Self::is_family_member(&env, &proposer) {
-            panic!("Only family members can propose transactions");
+        caller: Address,
+        member_address: Address,
+        new_limit: i128,
+        reason: String,
+    ) -> Result<bool, Error> {
+        caller.require_auth();
+        if reason.is_empty() {
+            return Err(Error::ReasonRequired);
         }
+        Self::set_spending_limit(&env, &caller, member_address, new_limit, Some(reason))?;
+        Ok(true)
+    }
 
-        let config_key = match tx_type {
-            TransactionType::RegularWithdrawal => {
-                Self::get_config_key(TransactionType::LargeWithdrawal)
-            }
-            _ => Self::get_config_key(tx_type),
-        };
+    pub fn set_require_change_reason(env: Env, caller: Address, required: bool) -> bool {
+        caller.require_auth();
+        Self::require_role_at_least(&env, &caller, FamilyRole::Owner);
+        Self::require_not_paused(&env);
 
-        let config: MultiSigConfig = env
-            .storage()
+        Self::extend_instance_ttl(&env);
+        env.storage()
             .instance()
-            .get(&config_key)
-            .expect("Multi-sig config not found");
-
-        let requires_multisig = match (&tx_type, &data) {
-            (TransactionType::RegularWithdrawal, TransactionData::Withdrawal(_, _, amount)) => {
-                *amount > config.spending_limit
-            }
-            (TransactionType::LargeWithdrawal, _) => true,
-            (TransactionType::RegularWithdrawal, _) => false,
-            _ => true,
-        };
-
-        if !requires_multisig {
-            return Self::execute_transaction_internal(&env, &proposer, &tx_type, &data, false);
-        }
+            .set(&symbol_short!("REQ_RSN"), &required);
 
-        Self::extend_instance_ttl(&env);
+        true
+    }
 
-        let mut next_tx_id: u64 = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("NEXT_TX"))
-            .unwrap_or(1);
+    pub fn get_require_change_reason(env: Env) -> bool {
+        Self::is_change_reason_required(&env)
+    }
 
-        let tx_id = next_tx_id;
-        next_tx_id += 1;
+    /// When on, limit increases are refused with `MemberOverLimit` while the
+    /// member's current-window spend already exceeds their current limit.
+    pub fn set_block_increase_when_over(env: Env, caller: Address, enabled: bool) -> bool {
+        caller.require_auth();
+        Self::require_role_at_least(&env, &caller, FamilyRole::Owner);
+        Self::require_not_paused(&env);
 
+        Self::extend_instance_ttl(&env);
         env.storage()
             .instance()
-            .set(&symbol_short!("NEXT_TX"), &next_tx_id);
-
-        let timestamp = env.ledger().timestamp();
-        let mut signatures = Vec::new(&env);
-        signatures.push_back(proposer.clone());
-
-        let pending_tx = PendingTransaction {
-            tx_id,
-            tx_type,
-            proposer: proposer.clone(),
-            signatures,
-            created_at: timestamp,
-            expires_at: timestamp + SIGNATURE_EXPIRATION,
-            data: data.clone(),
-        };
+            .set(&symbol_short!("BLK_INC"), &enabled);
 
-        let mut pending_txs: Map<u64, PendingTransaction> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("PEND_TXS"))
-            .expect("Pending transactions map not initialized");
+        true
+    }
 
-        pending_txs.set(tx_id, pending_tx);
+    pub fn get_block_increase_when_over(env: Env) -> bool {
         env.storage()
             .instance()
-            .set(&symbol_short!("PEND_TXS"), &pending_txs);
-
-        tx_id
+            .get(&symbol_short!("BLK_INC"))
+            .unwrap_or(false)
     }
 
-    pub fn sign_transaction(env: Env, signer: Address, tx_id: u64) -> bool {
-        signer.require_auth();
+    /// Restrict Member/Viewer spending limits to `[min_limit, max_limit]`
+    /// from now on; existing limits are left as they are. `0` leaves a bound
+    /// unset, and clearing both removes the restriction.
+    pub fn set_limit_bounds(
+        env: Env,
+        owner: Address,
+        min_limit: i128,
+        max_limit: i128,
+    ) -> Result<bool, Error> {
+        owner.require_auth();
+        if !Self::has_owner_rights(&env, &owner) {
+            return Err(Error::Unauthorized);
+        }
         Self::require_not_paused(&env);
-        Self::require_role_at_least(&env, &signer, FamilyRole::Member);
-
-        if !Self::is_family_member(&env, &signer) {
-            panic!("Only family members can sign transactions");
+        if min_limit < 0 || max_limit < 0 || (max_limit > 0 && min_limit > max_limit) {
+            return Err(Error::InvalidSpendingLimit);
         }
 
         Self::extend_instance_ttl(&env);
+        if min_limit == 0 && max_limit == 0 {
+            env.storage().instance().remove(&symbol_short!("LIM_BND"));
+        } else {
+            env.storage().instance().set(
+                &symbol_short!("LIM_BND"),
+                &LimitBounds {
+                    min_limit,
+                    max_limit,
+                },
+            );
+        }
 
-        let mut pending_txs: Map<u64, PendingTransaction> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("PEND_TXS"))
-            .expect("Pending transactions map not initialized");
-
-        let mut pending_tx = pending_txs.get(tx_id).expect("Transaction not found");
+        Ok(true)
+    }
 
-        let current_time = env.ledger().timestamp();
-        if current_time > pending_tx.expires_at {
-            panic!("Transaction expired");
-        }
+    pub fn get_limit_bounds(env: Env) -> Option<LimitBounds> {
+        env.storage().instance().get(&symbol_short!("LIM_BND"))
+    }
 
-        for sig in pending_tx.signatures.iter() {
-            if sig.clone() == signer {
-                panic!("Already signed this transaction");
-            }
+    /// Role and limit for `quick_add_member`. Owner only; validated like an
+    /// `add_member` call with the same role and limit.
+    pub fn set_defaults(
+        env: Env,
+        owner: Address,
+        role: FamilyRole,
+        spending_limit: i128,
+    ) -> Result<bool, Error> {
+        owner.require_auth();
+        if !Self::has_owner_rights(&env, &owner) {
+            return Err(Error::Unauthorized);
         }
-
-        let config: MultiSigConfig = env
-            .storage()
-            .instance()
-            .get(&Self::get_config_key(pending_tx.tx_type))
-            .expect("Multi-sig config not found");
-
-        let mut is_authorized = false;
-        for authorized_signer in config.signers.iter() {
-            if authorized_signer.clone() == signer {
-                is_authorized = true;
-                break;
-            }
+        Self::require_not_paused(&env);
+        if role == FamilyRole::Owner {
+            return Err(Error::InvalidRole);
         }
-
-        if !is_authorized {
-            panic!("Signer not authorized for this transaction type");
+        if spending_limit < 0 {
+            return Err(Error::InvalidSpendingLimit);
         }
+        Self::check_limit_bounds(&env, role, spending_limit)?;
 
-        pending_tx.signatures.push_back(signer.clone());
+        Self::extend_instance_ttl(&env);
+        env.storage().instance().set(
+            &symbol_short!("MEM_DEF"),
+            &MemberDefaults {
+                role,
+                spending_limit,
+            },
+        );
 
-        if pending_tx.signatures.len() >= config.threshold {
-            let executed = Self::execute_transaction_internal(
-                &env,
-                &pending_tx.proposer,
-                &pending_tx.tx_type,
-                &pending_tx.data,
-                true,
-            );
+        Ok(true)
+    }
 
-            if executed == 0 {
-                pending_txs.remove(tx_id);
-                env.storage()
-                    .instance()
-                    .set(&symbol_short!("PEND_TXS"), &pending_txs);
+    pub fn get_defaults(env: Env) -> Option<MemberDefaults> {
+        env.storage().instance().get(&symbol_short!("MEM_DEF"))
+    }
 
-                let mut executed_txs: Map<u64, bool> = env
-                    .storage()
-                    .instance()
-                    .get(&symbol_short!("EXEC_TXS"))
-                    .expect("Executed transactions map not initialized");
+    /// Toggle the owner-addressed alert published whenever `spend` declines.
+    pub fn set_decline_alerts(env: Env, caller: Address, enabled: bool) -> bool {
+        caller.require_auth();
+        Self::require_role_at_least(&env, &caller, FamilyRole::Owner);
+        Self::require_not_paused(&env);
 
-                executed_txs.set(tx_id, true);
-                env.storage()
-                    .instance()
-                    .set(&symbol_short!("EXEC_TXS"), &executed_txs);
-            }
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("DCL_ALRT"), &enabled);
 
-            return true;
-        }
+        true
+    }
 
-        pending_txs.set(tx_id, pending_tx);
+    pub fn get_decline_alerts(env: Env) -> bool {
         env.storage()
             .instance()
-            .set(&symbol_short!("PEND_TXS"), &pending_txs);
+            .get(&symbol_short!("DCL_ALRT"))
+            .unwrap_or(false)
+    }
 
-        true
+    /// Check if `caller` is allowed to spend `amount`.
+    ///
+    /// Rules (checked in order):
+    /// 1. Unknown address → false
+    /// 2. Negative amount → false
+    /// 3. Frozen or inactive member → false
+    /// 4. Past the member's `limit_expires_at` → false
+    /// 5. Owner / Admin → always true (unlimited)
+    /// 6. Member with `spending_limit == 0` → unlimited → true
+    /// 7. Member with `spending_limit > 0` → true iff `amount` is within the
+    ///    age-scaled limit (`get_effective_limit`)
+    pub fn check_spending_limit(env: Env, caller: Address, amount: i128) -> bool {
+        Self::evaluate_spend(env, caller, amount) == SpendCheckResult::Allowed
     }
 
-    pub fn withdraw(
-        env: Env,
-        proposer: Address,
-        token: Address,
-        recipient: Address,
-        amount: i128,
-    ) -> u64 {
-        if amount <= 0 {
-            panic!("Amount must be positive");
+    /// `check_spending_limit` for each `(member, amount)` pair in one call,
+    /// results in input order. At most `MAX_BATCH_MEMBERS` checks, else
+    /// `BatchTooLarge`.
+    pub fn check_spending_limits_batch(env: Env, checks: Vec<(Address, i128)>) -> Vec<bool> {
+        if checks.len() > MAX_BATCH_MEMBERS {
+            panic_with_error!(&env, Error::BatchTooLarge);
+        }
+        let mut results = Vec::new(&env);
+        for (member, amount) in checks.iter() {
+            results.push_back(Self::check_spending_limit(env.clone(), member, amount));
         }
+        results
+    }
 
-        let config: MultiSigConfig = env
+    /// `check_spending_limit` with the reason for a refusal, following the
+    /// same rules in the same order. Frozen members report
+    /// `MemberInactive`.
+    pub fn evaluate_spend(env: Env, caller: Address, amount: i128) -> SpendCheckResult {
+        Self::require_initialized(&env);
+        if amount < 0 {
+            return SpendCheckResult::InvalidAmount;
+        }
+
+        let members: Map<Address, FamilyMember> = env
             .storage()
             .instance()
-            .get(&Self::get_config_key(TransactionType::LargeWithdrawal))
-            .expect("Multi-sig config not found");
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
 
-        let tx_type = if amount > config.spending_limit {
-            TransactionType::LargeWithdrawal
-        } else {
-            TransactionType::RegularWithdrawal
+        let member = match members.get(caller) {
+            Some(m) => m,
+            None => return SpendCheckResult::MemberNotFound,
         };
 
-        Self::propose_transaction(
-            env,
-            proposer,
-            tx_type,
-            TransactionData::Withdrawal(token, recipient, amount),
-        )
+        if member.frozen || !member.active {
+            return SpendCheckResult::MemberInactive;
+        }
+        if Self::limit_expired(&env, &member) {
+            return SpendCheckResult::LimitExpired;
+        }
+
+        // Owner and Admin are never restricted
+        if member.role == FamilyRole::Owner || member.role == FamilyRole::Admin {
+            return SpendCheckResult::Allowed;
+        }
+
+        // 0 means unlimited for regular members too
+        let limit = Self::effective_limit(&env, &member);
+        if limit == 0 || amount <= limit {
+            SpendCheckResult::Allowed
+        } else {
+            SpendCheckResult::OverLimit
+        }
     }
 
-    pub fn propose_split_config_change(
+    /// Set `member_address`'s per-transaction cap for `token`. Members may
+    /// only spend tokens that have a configured cap (`0` = unlimited for
+    /// that token); see `check_token_spending_limit`.
+    pub fn set_token_limit(
         env: Env,
-        proposer: Address,
-        spending_percent: u32,
-        savings_percent: u32,
-        bills_percent: u32,
-        insurance_percent: u32,
-    ) -> u64 {
-        if spending_percent + savings_percent + bills_percent + insurance_percent != 100 {
-            panic!("Percentages must sum to 100");
+        caller: Address,
+        member_address: Address,
+        token: Address,
+        limit: i128,
+    ) -> Result<bool, Error> {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::is_owner_or_admin(&env, &caller) {
+            return Err(Error::Unauthorized);
+        }
+        if limit < 0 {
+            return Err(Error::InvalidSpendingLimit);
         }
 
-        Self::propose_transaction(
-            env,
-            proposer,
-            TransactionType::SplitConfigChange,
-            TransactionData::SplitConfigChange(
-                spending_percent,
-                savings_percent,
-                bills_percent,
-                insurance_percent,
-            ),
-        )
+        let members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        let record = members
+            .get(member_address.clone())
+            .ok_or(Error::MemberNotFound)?;
+        if !Self::can_manage_member(&members, &caller, &record) {
+            return Err(Error::Unauthorized);
+        }
+
+        let mut token_limits: Map<Address, Map<Address, i128>> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("TOK_LIM"))
+            .unwrap_or_else(|| Map::new(&env));
+        let mut limits = token_limits
+            .get(member_address.clone())
+            .unwrap_or_else(|| Map::new(&env));
+        limits.set(token, limit);
+        token_limits.set(member_address, limits);
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("TOK_LIM"), &token_limits);
+
+        Ok(true)
     }
 
-    pub fn propose_role_change(
-        env: Env,
-        proposer: Address,
-        member: Address,
-        new_role: FamilyRole,
-    ) -> u64 {
-        Self::propose_transaction(
-            env,
-            proposer,
-            TransactionType::RoleChange,
-            TransactionData::RoleChange(member, new_role),
-        )
+    /// Per-token caps configured for `member_address`, keyed by token.
+    pub fn get_token_limits(env: Env, member_address: Address) -> Map<Address, i128> {
+        env.storage()
+            .instance()
+            .get::<_, Map<Address, Map<Address, i128>>>(&symbol_short!("TOK_LIM"))
+            .and_then(|token_limits| token_limits.get(member_address))
+            .unwrap_or_else(|| Map::new(&env))
     }
 
-    pub fn propose_emergency_transfer(
+    /// Check if `caller` is allowed to spend `amount` of `token`.
+    ///
+    /// Same rules as `check_spending_limit`, except that a regular member's
+    /// cap comes from `set_token_limit`: a token with no configured cap is
+    /// rejected, `0` is unlimited, otherwise `amount <= cap`.
+    pub fn check_token_spending_limit(
         env: Env,
-        proposer: Address,
+        caller: Address,
         token: Address,
-        recipient: Address,
         amount: i128,
-    ) -> u64 {
-        if amount <= 0 {
-            panic!("Amount must be positive");
+    ) -> bool {
+        Self::require_initialized(&env);
+        if amount < 0 {
+            return false;
         }
 
-        let em_mode: bool = env
+        let members: Map<Address, FamilyMember> = env
             .storage()
             .instance()
-            .get(&symbol_short!("EM_MODE"))
-            .unwrap_or(false);
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
 
-        if em_mode {
-            return Self::execute_emergency_transfer_now(env, proposer, token, recipient, amount);
-        }
+        let member = match members.get(caller.clone()) {
+            Some(m) => m,
+            None => return false,
+        };
 
-        Self::propose_transaction(
-            env,
-            proposer,
-            TransactionType::EmergencyTransfer,
-            TransactionData::EmergencyTransfer(token, recipient, amount),
-        )
-    }
+        if member.frozen || !member.active || Self::limit_expired(&env, &member) {
+            return false;
+        }
 
-    pub fn propose_policy_cancellation(env: Env, proposer: Address, policy_id: u32) -> u64 {
-        Self::propose_transaction(
-            env,
-            proposer,
-            TransactionType::PolicyCancellation,
-            TransactionData::PolicyCancellation(policy_id),
-        )
+        Self::token_cap_allows(&env, &member, &token, amount)
     }
 
-    pub fn configure_emergency(
+    /// Set `member_address`'s per-transaction cap for spending `category`
+    /// (e.g. "school"). `0` = unlimited for that category. Categories
+    /// without a cap fall back to the member's `spending_limit`.
+    pub fn set_category_limit(
         env: Env,
         caller: Address,
-        max_amount: i128,
-        cooldown: u64,
-        min_balance: i128,
-    ) -> bool {
+        member_address: Address,
+        category: String,
+        limit: i128,
+    ) -> Result<bool, Error> {
         caller.require_auth();
         Self::require_not_paused(&env);
 
         if !Self::is_owner_or_admin(&env, &caller) {
-            panic!("Only Owner or Admin can configure emergency settings");
-        }
-        if max_amount <= 0 {
-            panic!("Emergency max amount must be positive");
+            return Err(Error::Unauthorized);
         }
-        if min_balance < 0 {
-            panic!("Emergency min balance must be non-negative");
+        if limit < 0 {
+            return Err(Error::InvalidSpendingLimit);
         }
 
-        Self::extend_instance_ttl(&env);
-
-        env.storage().instance().set(
-            &symbol_short!("EM_CONF"),
-            &EmergencyConfig {
-                max_amount,
-                cooldown,
-                min_balance,
-            },
-        );
-
-        true
-    }
-
-    pub fn set_emergency_mode(env: Env, caller: Address, enabled: bool) -> bool {
-        caller.require_auth();
-        Self::require_not_paused(&env);
-
-        if !Self::is_owner_or_admin(&env, &caller) {
-            panic!("Only Owner or Admin can change emergency mode");
+        let members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        let record = members
+            .get(member_address.clone())
+            .ok_or(Error::MemberNotFound)?;
+        if !Self::can_manage_member(&members, &caller, &record) {
+            return Err(Error::Unauthorized);
         }
 
-        Self::extend_instance_ttl(&env);
+        let mut category_limits: Map<Address, Map<String, i128>> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("CAT_LIM"))
+            .unwrap_or_else(|| Map::new(&env));
+        let mut limits = category_limits
+            .get(member_address.clone())
+            .unwrap_or_else(|| Map::new(&env));
+        limits.set(category, limit);
+        category_limits.set(member_address, limits);
 
+        Self::extend_instance_ttl(&env);
         env.storage()
             .instance()
-            .set(&symbol_short!("EM_MODE"), &enabled);
+            .set(&symbol_short!("CAT_LIM"), &category_limits);
 
-        let event = if enabled {
-            EmergencyEvent::ModeOn
-        } else {
-            EmergencyEvent::ModeOff
-        };
-        env.events()
-            .publish((symbol_short!("emerg"), event), caller);
+        Ok(true)
+    }
 
-        true
+    /// Per-category caps configured for `member_address`.
+    pub fn get_category_limits(env: Env, member_address: Address) -> Map<String, i128> {
+        env.storage()
+            .instance()
+            .get::<_, Map<Address, Map<String, i128>>>(&symbol_short!("CAT_LIM"))
+            .and_then(|category_limits| category_limits.get(member_address))
+            .unwrap_or_else(|| Map::new(&env))
     }
 
-    pub fn add_family_member(env: Env, caller: Address, member: Address, role: FamilyRole) -> bool {
-        caller.require_auth();
+    /// Change only `member_address`'s display name, leaving its role and
+    /// limit untouched. Owner only. Panics on an empty name or one over
+    /// `MAX_NAME_LEN` bytes, or with `enforce_unique_names` on, on a name
+    /// another member already uses; returns `false` for non-members.
+    pub fn update_member_name(
+        env: Env,
+        owner: Address,
+        member_address: Address,
+        new_name: String,
+    ) -> bool {
+        owner.require_auth();
+        if !Self::has_owner_rights(&env, &owner) {
+            panic!("Only Owner can update member names");
+        }
         Self::require_not_paused(&env);
-        if role == FamilyRole::Owner {
-            panic!("Cannot add Owner via add_family_member");
+        if new_name.is_empty() {
+            panic!("Name cannot be empty");
         }
-        if !Self::is_owner_or_admin(&env, &caller) {
-            panic!("Only Owner or Admin can add family members");
+        if new_name.len() > MAX_NAME_LEN {
+            panic!("Name too long");
         }
 
-        Self::extend_instance_ttl(&env);
-
         let mut members: Map<Address, FamilyMember> = env
             .storage()
             .instance()
             .get(&symbol_short!("MEMBERS"))
-            .expect("Wallet not initialized");
-
-        let timestamp = env.ledger().timestamp();
-        members.set(
-            member.clone(),
-            FamilyMember {
-                address: member.clone(),
-                role,
-                spending_limit: 0,
-                added_at: timestamp,
-            },
-        );
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        let mut record = match members.get(member_address.clone()) {
+            Some(record) => record,
+            None => return false,
+        };
+        if Self::get_enforce_unique_names(env.clone()) {
+            for (address, other) in members.iter() {
+                if address != member_address && other.name.as_ref() == Some(&new_name) {
+                    panic!("Member name already in use");
+                }
+            }
+        }
+        record.name = Some(new_name);
+        members.set(member_address.clone(), record);
 
+        Self::extend_instance_ttl(&env);
         env.storage()
             .instance()
             .set(&symbol_short!("MEMBERS"), &members);
-
-        Self::append_access_audit(&env, symbol_short!("add_mem"), &caller, Some(member), true);
+        Self::append_access_audit(
+            &env,
+            symbol_short!("upd_name"),
+            &owner,
+            Some(member_address),
+            true,
+        );
         true
     }
 
-    pub fn remove_family_member(env: Env, caller: Address, member: Address) -> bool {
-        caller.require_auth();
-        Self::require_not_paused(&env);
-
-        let owner: Address = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("OWNER"))
-            .expect("Wallet not initialized");
-
-        if caller != owner {
-            panic!("Only Owner can remove family members");
-        }
-        if member == owner {
-            panic!("Cannot remove owner");
+    /// Reject `update_member_name` calls that would give two members the
+    /// same name. Owner only; off by default. Names set before enabling
+    /// are not re-checked.
+    pub fn set_enforce_unique_names(
+        env: Env,
+        owner: Address,
+        enabled: bool,
+    ) -> Result<bool, Error> {
+        owner.require_auth();
+        if !Self::has_owner_rights(&env, &owner) {
+            return Err(Error::Unauthorized);
         }
+        Self::require_not_paused(&env);
 
         Self::extend_instance_ttl(&env);
-
-        let mut members: Map<Address, FamilyMember> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("MEMBERS"))
-            .expect("Wallet not initialized");
-
-        members.remove(member.clone());
         env.storage()
             .instance()
-            .set(&symbol_short!("MEMBERS"), &members);
+            .set(&symbol_short!("UNIQ_NAME"), &enabled);
 
-        Self::append_access_audit(&env, symbol_short!("rem_mem"), &caller, Some(member), true);
-        true
+        Ok(true)
     }
 
-    pub fn get_pending_transaction(env: Env, tx_id: u64) -> Option<PendingTransaction> {
-        let pending_txs: Map<u64, PendingTransaction> = env
-            .storage()
+    pub fn get_enforce_unique_names(env: Env) -> bool {
+        env.storage()
             .instance()
-            .get(&symbol_short!("PEND_TXS"))
-            .expect("Pending transactions map not initialized");
-
-        pending_txs.get(tx_id)
+            .get(&symbol_short!("UNIQ_NAME"))
+            .unwrap_or(false)
     }
 
-    pub fn get_multisig_config(env: Env, tx_type: TransactionType) -> Option<MultiSigConfig> {
-        env.storage().instance().get(&Self::get_config_key(tx_type))
-    }
+    /// Attach a free-form `key` => `value` label (e.g. "school") to a
+    /// member. Owner only. An empty `value` removes the key; a member holds
+    /// at most `MAX_MEMBER_METADATA_KEYS` keys.
+    pub fn set_member_metadata(
+        env: Env,
+        owner: Address,
+        member_address: Address,
+        key: String,
+        value: String,
+    ) -> Result<bool, Error> {
+        owner.require_auth();
+        if !Self::has_owner_rights(&env, &owner) {
+            return Err(Error::Unauthorized);
+        }
+        Self::require_not_paused(&env);
 
-    pub fn get_family_member(env: Env, member: Address) -> Option<FamilyMember> {
         let members: Map<Address, FamilyMember> = env
             .storage()
             .instance()
             .get(&symbol_short!("MEMBERS"))
-            .expect("Wallet not initialized");
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        if !members.contains_key(member_address.clone()) {
+            return Err(Error::MemberNotFound);
+        }
 
-        members.get(member)
-    }
+        let mut metadata: Map<Address, Map<String, String>> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("META"))
+            .unwrap_or_else(|| Map::new(&env));
+        let mut entries = metadata
+            .get(member_address.clone())
+            .unwrap_or_else(|| Map::new(&env));
+        if value.is_empty() {
+            entries.remove(key);
+        } else {
+            if !entries.contains_key(key.clone()) && entries.len() >= MAX_MEMBER_METADATA_KEYS {
+                return Err(Error::MetadataLimitExceeded);
+            }
+            entries.set(key, value);
+        }
+        if entries.is_empty() {
+            metadata.remove(member_address);
+        } else {
+            metadata.set(member_address, entries);
+        }
 
-    pub fn get_owner(env: Env) -> Address {
+        Self::extend_instance_ttl(&env);
         env.storage()
             .instance()
-            .get(&symbol_short!("OWNER"))
-            .expect("Wallet not initialized")
-    }
+            .set(&symbol_short!("META"), &metadata);
 
-    pub fn get_emergency_config(env: Env) -> Option<EmergencyConfig> {
-        env.storage().instance().get(&symbol_short!("EM_CONF"))
+        Ok(true)
     }
 
-    pub fn is_emergency_mode(env: Env) -> bool {
+    pub fn get_member_metadata(env: Env, member_address: Address, key: String) -> Option<String> {
         env.storage()
             .instance()
-            .get(&symbol_short!("EM_MODE"))
-            .unwrap_or(false)
+            .get::<_, Map<Address, Map<String, String>>>(&symbol_short!("META"))
+            .and_then(|metadata| metadata.get(member_address))
+            .and_then(|entries| entries.get(key))
     }
 
-    pub fn get_last_emergency_at(env: Env) -> Option<u64> {
-        let ts: u64 = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("EM_LAST"))
-            .unwrap_or(0u64);
-        if ts == 0 {
-            None
-        } else {
-            Some(ts)
+    /// Price feed used by `check_spending_limit_in_ref`. Owner only;
+    /// `None` removes it.
+    pub fn set_price_oracle(
+        env: Env,
+        owner: Address,
+        oracle: Option<Address>,
+    ) -> Result<bool, Error> {
+        owner.require_auth();
+        if !Self::has_owner_rights(&env, &owner) {
+            return Err(Error::Unauthorized);
         }
-    }
-
-    pub fn archive_old_transactions(env: Env, caller: Address, before_timestamp: u64) -> u32 {
-        caller.require_auth();
         Self::require_not_paused(&env);
 
-        if !Self::is_owner_or_admin(&env, &caller) {
-            panic!("Only Owner or Admin can archive transactions");
-        }
-
         Self::extend_instance_ttl(&env);
+        match oracle {
+            Some(oracle) => env
+                .storage()
+                .instance()
+                .set(&symbol_short!("ORACLE"), &oracle),
+            None => env.storage().instance().remove(&symbol_short!("ORACLE")),
+        }
 
-        let executed_txs: Map<u64, bool> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("EXEC_TXS"))
-            .unwrap_or_else(|| Map::new(&env));
+        Ok(true)
+    }
 
-        let mut archived: Map<u64, ArchivedTransaction> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("ARCH_TX"))
-            .unwrap_or_else(|| Map::new(&env));
+    pub fn get_price_oracle(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("ORACLE"))
+    }
 
-        let current_time = env.ledger().timestamp();
-        let mut archived_count = 0u32;
+    /// `check_spending_limit` for limits set in the reference currency:
+    /// `amount` of `token` is converted with the configured oracle's price
+    /// before the comparison. Without an oracle `amount` is compared as-is.
+    /// A non-positive price or an overflowing conversion is refused.
+    pub fn check_spending_limit_in_ref(
+        env: Env,
+        caller: Address,
+        token: Address,
+        amount: i128,
+    ) -> bool {
+        let oracle = match Self::get_price_oracle(env.clone()) {
+            Some(oracle) => oracle,
+            None => return Self::check_spending_limit(env, caller, amount),
+        };
 
-        for (tx_id, _) in executed_txs.iter() {
-            let archived_tx = ArchivedTransaction {
-                tx_id,
-                tx_type: TransactionType::RegularWithdrawal,
-                proposer: caller.clone(),
-                executed_at: before_timestamp,
-                archived_at: current_time,
-            };
-            archived.set(tx_id, archived_tx);
-            archived_count += 1;
+        let price = PriceOracleClient::new(&env, &oracle).price(&token);
+        if price <= 0 {
+            return false;
+        }
+        match amount.checked_mul(price) {
+            Some(scaled) => Self::check_spending_limit(env, caller, scaled / PRICE_SCALE),
+            None => false,
         }
+    }
 
-        if archived_count > 0 {
-            env.storage()
-                .instance()
-                .set(&symbol_short!("EXEC_TXS"), &Map::<u64, bool>::new(&env));
+    /// Check if `caller` is allowed to spend `amount` in `category`.
+    ///
+    /// A configured category cap replaces `spending_limit` for regular
+    /// members (`0` = unlimited, otherwise `amount <= cap`); with no cap for
+    /// the category this is exactly `check_spending_limit`.
+    pub fn check_category_spending(
+        env: Env,
+        caller: Address,
+        category: String,
+        amount: i128,
+    ) -> bool {
+        let cap = match Self::get_category_limits(env.clone(), caller.clone()).get(category) {
+            Some(cap) => cap,
+            None => return Self::check_spending_limit(env, caller, amount),
+        };
+
+        Self::require_initialized(&env);
+        if amount < 0 {
+            return false;
         }
 
-        env.storage()
+        let members: Map<Address, FamilyMember> = env
+            .storage()
             .instance()
-            .set(&symbol_short!("ARCH_TX"), &archived);
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        let member = match members.get(caller) {
+            Some(m) => m,
+            None => return false,
+        };
 
-        Self::extend_archive_ttl(&env);
-        Self::update_storage_stats(&env);
+        if member.frozen || !member.active || Self::limit_expired(&env, &member) {
+            return false;
+        }
 
-        env.events().publish(
-            (symbol_short!("wallet"), ArchiveEvent::TransactionsArchived),
-            (archived_count, caller),
-        );
+        if member.role == FamilyRole::Owner || member.role == FamilyRole::Admin {
+            return true;
+        }
 
-        archived_count
+        cap == 0 || amount <= cap
     }
 
-    pub fn get_archived_transactions(env: Env, limit: u32) -> Vec<ArchivedTransaction> {
-        let archived: Map<u64, ArchivedTransaction> = env
+    /// Freeze or unfreeze a member's spending. The owner cannot be frozen.
+    pub fn set_member_frozen(
+        env: Env,
+        caller: Address,
+        member_address: Address,
+        frozen: bool,
+    ) -> Result<bool, Error> {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::is_owner_or_admin(&env, &caller) {
+            return Err(Error::Unauthorized);
+        }
+
+        let mut members: Map<Address, FamilyMember> = env
             .storage()
             .instance()
-            .get(&symbol_short!("ARCH_TX"))
-            .unwrap_or_else(|| Map::new(&env));
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
 
-        let mut result = Vec::new(&env);
-        for (count, (_, tx)) in archived.iter().enumerate() {
-            if count as u32 >= limit {
-                break;
-            }
-            result.push_back(tx);
+        let mut record = members
+            .get(member_address.clone())
+            .ok_or(Error::MemberNotFound)?;
+        if record.role == FamilyRole::Owner {
+            return Err(Error::InvalidRole);
         }
-        result
+        if !Self::can_manage_member(&members, &caller, &record) {
+            return Err(Error::Unauthorized);
+        }
+
+        record.frozen = frozen;
+        members.set(member_address.clone(), record);
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members);
+
+        let op = if frozen {
+            symbol_short!("frozen")
+        } else {
+            symbol_short!("unfrozen")
+        };
+        Self::append_access_audit(
+            &env,
+            op.clone(),
+            &caller,
+            Some(member_address.clone()),
+            true,
+        );
+        env.events()
+            .publish((op, symbol_short!("member")), member_address);
+
+        Ok(true)
     }
 
-    pub fn cleanup_expired_pending(env: Env, caller: Address) -> u32 {
+    /// Set the window over which a member's spending limit applies. Starts a
+    /// fresh window from the current ledger timestamp.
+    pub fn set_limit_period(
+        env: Env,
+        caller: Address,
+        member_address: Address,
+        period: LimitPeriod,
+    ) -> Result<bool, Error> {
         caller.require_auth();
         Self::require_not_paused(&env);
 
         if !Self::is_owner_or_admin(&env, &caller) {
-            panic!("Only Owner or Admin can cleanup expired transactions");
+            return Err(Error::Unauthorized);
         }
 
-        Self::extend_instance_ttl(&env);
-
-        let mut pending_txs: Map<u64, PendingTransaction> = env
+        let mut members: Map<Address, FamilyMember> = env
             .storage()
             .instance()
-            .get(&symbol_short!("PEND_TXS"))
-            .unwrap_or_else(|| Map::new(&env));
-
-        let current_time = env.ledger().timestamp();
-        let mut removed_count = 0u32;
-        let mut to_remove: Vec<u64> = Vec::new(&env);
-
-        for (tx_id, tx) in pending_txs.iter() {
-            if tx.expires_at < current_time {
-                to_remove.push_back(tx_id);
-                removed_count += 1;
-            }
-        }
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
 
-        for i in 0..to_remove.len() {
-            if let Some(id) = to_remove.get(i) {
-                pending_txs.remove(id);
-            }
+        let mut record = members
+            .get(member_address.clone())
+            .ok_or(Error::MemberNotFound)?;
+        if !Self::can_manage_member(&members, &caller, &record) {
+            return Err(Error::Unauthorized);
         }
+        record.limit_period = period;
+        record.period_start = env.ledger().timestamp();
+        record.spent_in_period = 0;
+        members.set(member_address, record);
 
+        Self::extend_instance_ttl(&env);
         env.storage()
             .instance()
-            .set(&symbol_short!("PEND_TXS"), &pending_txs);
-
-        Self::update_storage_stats(&env);
+            .set(&symbol_short!("MEMBERS"), &members);
 
-        env.events().publish(
-            (symbol_short!("wallet"), ArchiveEvent::ExpiredCleaned),
-            (removed_count, caller),
-        );
+        Ok(true)
+    }
 
-        removed_count
+    /// Record a spend by `member_address` against their periodic limit.
+    ///
+    /// The spending window is rolled forward first if its period has
+    /// elapsed. Fails with `SpendingLimitExceeded` when the spend would take
+    /// the member past `spending_limit` for the current window.
+    pub fn record_spend(env: Env, member_address: Address, amount: i128) -> Result<bool, Error> {
+        member_address.require_auth();
+        Self::commit_spend(&env, &member_address, amount)?;
+        Ok(true)
     }
 
-    pub fn get_storage_stats(env: Env) -> StorageStats {
-        env.storage()
-            .instance()
-            .get(&symbol_short!("STOR_STAT"))
-            .unwrap_or(StorageStats {
-                pending_transactions: 0,
-                archived_transactions: 0,
-                total_members: 0,
-                last_updated: 0,
-            })
+    /// `record_spend` authorized by one of the member's delegate signers
+    /// (see `add_delegate`) instead of the member. The spend counts against
+    /// the member's own limit.
+    pub fn record_spend_as_delegate(
+        env: Env,
+        delegate: Address,
+        member_address: Address,
+        amount: i128,
+    ) -> Result<bool, Error> {
+        Self::require_delegate(&env, &delegate, &member_address)?;
+        Self::commit_spend(&env, &member_address, amount)?;
+        Ok(true)
     }
 
-    pub fn set_role_expiry(
+    /// Let `delegate` (e.g. a child's device key) sign spends for `member`.
+    /// Owner only; `member` must exist and cannot delegate to itself.
+    /// Adding an existing delegate is a no-op.
+    pub fn add_delegate(
         env: Env,
-        caller: Address,
+        owner: Address,
         member: Address,
-        expires_at: Option<u64>,
-    ) -> bool {
-        caller.require_auth();
-        Self::require_role_at_least(&env, &caller, FamilyRole::Admin);
+        delegate: Address,
+    ) -> Result<bool, Error> {
+        owner.require_auth();
+        if !Self::has_owner_rights(&env, &owner) {
+            return Err(Error::Unauthorized);
+        }
         Self::require_not_paused(&env);
-        Self::extend_instance_ttl(&env);
-        let mut m: Map<Address, u64> = env
+        if !Self::is_member(env.clone(), member.clone()) {
+            return Err(Error::MemberNotFound);
+        }
+        if delegate == member {
+            return Err(Error::InvalidRole);
+        }
+
+        let mut delegates: Map<Address, Vec<Address>> = env
             .storage()
             .instance()
-            .get(&symbol_short!("ROLE_EXP"))
+            .get(&symbol_short!("DELEGATES"))
             .unwrap_or_else(|| Map::new(&env));
-        match expires_at {
-            Some(t) => m.set(member.clone(), t),
-            None => {
-                m.remove(member.clone());
-            }
+        let mut list = delegates
+            .get(member.clone())
+            .unwrap_or_else(|| Vec::new(&env));
+        if !list.contains(&delegate) {
+            list.push_back(delegate.clone());
         }
-        env.storage().instance().set(&symbol_short!("ROLE_EXP"), &m);
-        Self::append_access_audit(&env, symbol_short!("role_exp"), &caller, Some(member), true);
-        true
-    }
-
-    pub fn get_role_expiry_public(env: Env, address: Address) -> Option<u64> {
-        Self::get_role_expiry(&env, &address)
-    }
+        delegates.set(member.clone(), list);
 
-    pub fn pause(env: Env, caller: Address) -> bool {
-        caller.require_auth();
-        Self::require_role_at_least(&env, &caller, FamilyRole::Admin);
-        let admin = Self::get_pause_admin(&env).unwrap_or_else(|| {
-            env.storage()
-                .instance()
-                .get(&symbol_short!("OWNER"))
-                .expect("Wallet not initialized")
-        });
-        if admin != caller {
-            panic!("Only pause admin can pause");
-        }
+        Self::extend_instance_ttl(&env);
         env.storage()
             .instance()
-            .set(&symbol_short!("PAUSED"), &true);
-        env.events()
-            .publish((symbol_short!("wallet"), symbol_short!("paused")), ());
-        true
+            .set(&symbol_short!("DELEGATES"), &delegates);
+        Self::append_access_audit(&env, symbol_short!("add_dlg"), &owner, Some(member), true);
+
+        Ok(true)
     }
 
-    pub fn unpause(env: Env, caller: Address) -> bool {
-        caller.require_auth();
-        let admin = Self::get_pause_admin(&env).unwrap_or_else(|| {
-            env.storage()
-                .instance()
-                .get(&symbol_short!("OWNER"))
-                .expect("Wallet not initialized")
-        });
-        if admin != caller {
-            panic!("Only pause admin can unpause");
+    /// Revoke a delegate signer. Owner only. Returns whether `delegate` was
+    /// registered for `member`.
+    pub fn remove_delegate(
+        env: Env,
+        owner: Address,
+        member: Address,
+        delegate: Address,
+    ) -> Result<bool, Error> {
+        owner.require_auth();
+        if !Self::has_owner_rights(&env, &owner) {
+            return Err(Error::Unauthorized);
+        }
+
+        let mut delegates: Map<Address, Vec<Address>> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("DELEGATES"))
+            .unwrap_or_else(|| Map::new(&env));
+        let mut list = delegates
+            .get(member.clone())
+            .unwrap_or_else(|| Vec::new(&env));
+        let index = match list.first_index_of(&delegate) {
+            Some(index) => index,
+            None => return Ok(false),
+        };
+        list.remove(index);
+        if list.is_empty() {
+            delegates.remove(member.clone());
+        } else {
+            delegates.set(member.clone(), list);
         }
+
+        Self::extend_instance_ttl(&env);
         env.storage()
             .instance()
-            .set(&symbol_short!("PAUSED"), &false);
-        env.events()
-            .publish((symbol_short!("wallet"), symbol_short!("unpaused")), ());
-        true
+            .set(&symbol_short!("DELEGATES"), &delegates);
+        Self::append_access_audit(&env, symbol_short!("rm_dlg"), &owner, Some(member), true);
+
+        Ok(true)
     }
 
-    pub fn set_pause_admin(env: Env, caller: Address, new_admin: Address) -> bool {
-        caller.require_auth();
-        Self::require_role_at_least(&env, &caller, FamilyRole::Owner);
+    pub fn get_delegates(env: Env, member: Address) -> Vec<Address> {
         env.storage()
             .instance()
-            .set(&symbol_short!("PAUSE_ADM"), &new_admin);
-        true
+            .get::<_, Map<Address, Vec<Address>>>(&symbol_short!("DELEGATES"))
+            .and_then(|delegates| delegates.get(member))
+            .unwrap_or_else(|| Vec::new(&env))
     }
 
-    pub fn is_paused(env: Env) -> bool {
-        Self::get_global_paused(&env)
+    /// Spend `amount` on behalf of `member_address`.
+    ///
+    /// Returns `false` without changing state when the spend is declined:
+    /// non-positive amount, unknown or frozen member, or a spend that would
+    /// exceed the member's limit for the current window.
+    pub fn spend(env: Env, member_address: Address, amount: i128) -> bool {
+        member_address.require_auth();
+        Self::settle_spend(&env, &member_address, amount)
     }
 
-    pub fn get_version(env: Env) -> u32 {
-        env.storage()
-            .instance()
-            .get(&symbol_short!("VERSION"))
-            .unwrap_or(CONTRACT_VERSION)
+    /// `spend` signed by one of the member's delegates (see `add_delegate`)
+    /// instead of the member. Panics with `Unauthorized` for anyone else.
+    pub fn spend_as_delegate(
+        env: Env,
+        delegate: Address,
+        member_address: Address,
+        amount: i128,
+    ) -> bool {
+        if let Err(err) = Self::require_delegate(&env, &delegate, &member_address) {
+            panic_with_error!(&env, err);
+        }
+        Self::settle_spend(&env, &member_address, amount)
     }
 
-    fn get_upgrade_admin(env: &Env) -> Option<Address> {
-        env.storage().instance().get(&symbol_short!("UPG_ADM"))
+    /// Custodial spend: pay `amount` of `token` from the wallet contract to
+    /// `recipient` on `member_address`'s behalf. The spend is checked and
+    /// recorded exactly like `spend`, and reverts with nothing transferred
+    /// when a limit or the wallet's `token` holdings would be exceeded.
+    /// Member/Viewer spends must also fit the `set_token_limit` cap for
+    /// `token`; an unlisted token fails with `SpendingLimitExceeded`.
+    /// Draws on the shared treasury; the member's internal `balance` is not
+    /// debited. With recipient approval on, a first payment to a new
+    /// recipient returns `false` and waits for `approve_recipient`.
+    pub fn spend_token(
+        env: Env,
+        member_address: Address,
+        token: Address,
+        amount: i128,
+        recipient: Address,
+    ) -> Result<bool, Error> {
+        member_address.require_auth();
+        Self::settle_token_spend(&env, &member_address, &token, amount, &recipient)
     }
 
-    pub fn set_upgrade_admin(env: Env, caller: Address, new_admin: Address) -> bool {
-        caller.require_auth();
-        Self::require_role_at_least(&env, &caller, FamilyRole::Owner);
+    /// `spend_token` signed by one of the member's delegates (see
+    /// `add_delegate`) instead of the member.
+    pub fn spend_token_as_delegate(
+        env: Env,
+        delegate: Address,
+        member_address: Address,
+        token: Address,
+        amount: i128,
+        recipient: Address,
+    ) -> Result<bool, Error> {
+        Self::require_delegate(&env, &delegate, &member_address)?;
+        Self::settle_token_spend(&env, &member_address, &token, amount, &recipient)
+    }
+
+    /// Require owner approval before a Member/Viewer's first `spend_token`
+    /// to each recipient. Owner only. Recipients already paid stay
+    /// approved.
+    pub fn set_recipient_approval(env: Env, owner: Address, enabled: bool) -> Result<bool, Error> {
+        owner.require_auth();
+        if !Self::has_owner_rights(&env, &owner) {
+            return Err(Error::Unauthorized);
+        }
+        Self::require_not_paused(&env);
+
+        Self::extend_instance_ttl(&env);
         env.storage()
             .instance()
-            .set(&symbol_short!("UPG_ADM"), &new_admin);
-        true
+            .set(&symbol_short!("RCPT_APR"), &enabled);
+
+        Ok(true)
     }
 
-    pub fn set_version(env: Env, caller: Address, new_version: u32) -> bool {
-        caller.require_auth();
-        let admin = Self::get_upgrade_admin(&env).unwrap_or_else(|| {
-            env.storage()
-                .instance()
-                .get(&symbol_short!("OWNER"))
-                .expect("Wallet not initialized")
-        });
-        if admin != caller {
-            panic!("Only upgrade admin can set version");
-        }
-        let prev = Self::get_version(env.clone());
+    pub fn get_recipient_approval(env: Env) -> bool {
         env.storage()
             .instance()
-            .set(&symbol_short!("VERSION"), &new_version);
-        env.events().publish(
-            (symbol_short!("wallet"), symbol_short!("upgraded")),
-            (prev, new_version),
-        );
-        true
+            .get(&symbol_short!("RCPT_APR"))
+            .unwrap_or(false)
     }
 
-    pub fn batch_add_family_members(
+    /// Approve `recipient` for `member`, clearing any held first payment so
+    /// the member can retry it. Owner only.
+    pub fn approve_recipient(
         env: Env,
-        caller: Address,
-        members: Vec<BatchMemberItem>,
-    ) -> u32 {
-        caller.require_auth();
-        Self::require_role_at_least(&env, &caller, FamilyRole::Admin);
-        Self::require_not_paused(&env);
-        if members.len() > MAX_BATCH_MEMBERS {
-            panic!("Batch too large");
+        owner: Address,
+        member: Address,
+        recipient: Address,
+    ) -> Result<bool, Error> {
+        owner.require_auth();
+        if !Self::has_owner_rights(&env, &owner) {
+            return Err(Error::Unauthorized);
         }
-        Self::extend_instance_ttl(&env);
-        let mut members_map: Map<Address, FamilyMember> = env
+        if !Self::is_member(env.clone(), member.clone()) {
+            return Err(Error::MemberNotFound);
+        }
+
+        let mut pending: Map<Address, Vec<Address>> = env
             .storage()
             .instance()
-            .get(&symbol_short!("MEMBERS"))
-            .expect("Wallet not initialized");
-        let timestamp = env.ledger().timestamp();
-        let mut count = 0u32;
-        for item in members.iter() {
-            if item.role == FamilyRole::Owner {
-                panic!("Cannot add Owner via batch");
+            .get(&symbol_short!("RCPT_PEND"))
+            .unwrap_or_else(|| Map::new(&env));
+        let mut held = pending
+            .get(member.clone())
+            .unwrap_or_else(|| Vec::new(&env));
+        if let Some(index) = held.first_index_of(&recipient) {
+            held.remove(index);
+            if held.is_empty() {
+                pending.remove(member.clone());
+            } else {
+                pending.set(member.clone(), held);
             }
-            members_map.set(
-                item.address.clone(),
-                FamilyMember {
-                    address: item.address.clone(),
-                    role: item.role,
-                    spending_limit: 0,
-                    added_at: timestamp,
-                },
-            );
-            Self::append_access_audit(
-                &env,
-                symbol_short!("add_mem"),
-                &caller,
-                Some(item.address.clone()),
-                true,
-            );
-            count += 1;
+            env.storage()
+                .instance()
+                .set(&symbol_short!("RCPT_PEND"), &pending);
         }
+
+        Self::extend_instance_ttl(&env);
+        Self::remember_recipient(&env, &member, &recipient);
+        env.events().publish(
+            (symbol_short!("recip"), symbol_short!("approved"), member),
+            recipient,
+        );
+
+        Ok(true)
+    }
+
+    /// Recipients whose first payment from `member` awaits approval.
+    pub fn get_pending_recipients(env: Env, member: Address) -> Vec<Address> {
         env.storage()
             .instance()
-            .set(&symbol_short!("MEMBERS"), &members_map);
-        Self::update_storage_stats(&env);
-        count
+            .get::<_, Map<Address, Vec<Address>>>(&symbol_short!("RCPT_PEND"))
+            .and_then(|pending| pending.get(member))
+            .unwrap_or_else(|| Vec::new(&env))
     }
 
-    pub fn batch_remove_family_members(env: Env, caller: Address, addresses: Vec<Address>) -> u32 {
-        caller.require_auth();
-        Self::require_role_at_least(&env, &caller, FamilyRole::Owner);
-        let owner: Address = env
-            .storage()
+    /// Whether `member` has paid or been approved to pay `recipient`.
+    pub fn is_known_recipient(env: Env, member: Address, recipient: Address) -> bool {
+        env.storage()
             .instance()
-            .get(&symbol_short!("OWNER"))
-            .expect("Wallet not initialized");
-        if caller != owner {
-            panic!("Only Owner can remove members");
-        }
+            .get::<_, Map<Address, Vec<Address>>>(&symbol_short!("RCPT_KNWN"))
+            .and_then(|known| known.get(member))
+            .map(|known| known.contains(&recipient))
+            .unwrap_or(false)
+    }
+
+    /// Queue a spend of `amount` that `member_address` can execute once the
+    /// withdrawal delay in force now has elapsed. Limits are checked at
+    /// execution, not here. Returns the request id.
+    pub fn request_withdrawal(
+        env: Env,
+        member_address: Address,
+        amount: i128,
+    ) -> Result<u64, Error> {
+        member_address.require_auth();
         Self::require_not_paused(&env);
-        if addresses.len() > MAX_BATCH_MEMBERS {
-            panic!("Batch too large");
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if !Self::is_member(env.clone(), member_address.clone()) {
+            return Err(Error::MemberNotFound);
         }
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("WD_NEXT"))
+            .unwrap_or(1);
+        let now = env.ledger().timestamp();
+        let request = WithdrawalRequest {
+            id,
+            member: member_address.clone(),
+            amount,
+            requested_at: now,
+            executable_at: now.saturating_add(Self::get_withdrawal_delay(env.clone())),
+        };
+
+        let mut requests: Map<u64, WithdrawalRequest> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("WD_REQS"))
+            .unwrap_or_else(|| Map::new(&env));
+        requests.set(id, request);
+
         Self::extend_instance_ttl(&env);
-        let mut members_map: Map<Address, FamilyMember> = env
+        env.storage()
+            .instance()
+            .set(&symbol_short!("WD_REQS"), &requests);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("WD_NEXT"), &(id + 1));
+
+        env.events().publish(
+            (
+                symbol_short!("withdraw"),
+                symbol_short!("requested"),
+                member_address.clone(),
+            ),
+            (id, member_address, amount),
+        );
+
+        Ok(id)
+    }
+
+    /// Drop a queued withdrawal. Allowed for an owner (veto) and for the
+    /// member who requested it.
+    pub fn cancel_withdrawal(env: Env, caller: Address, id: u64) -> Result<bool, Error> {
+        caller.require_auth();
+
+        let mut requests: Map<u64, WithdrawalRequest> = env
             .storage()
             .instance()
-            .get(&symbol_short!("MEMBERS"))
-            .expect("Wallet not initialized");
-        let mut count = 0u32;
-        for addr in addresses.iter() {
-            if addr.clone() == owner {
-                panic!("Cannot remove owner");
-            }
-            if members_map.get(addr.clone()).is_some() {
-                members_map.remove(addr.clone());
-                Self::append_access_audit(
-                    &env,
-                    symbol_short!("rem_mem"),
-                    &caller,
-                    Some(addr.clone()),
-                    true,
-                );
-                count += 1;
-            }
+            .get(&symbol_short!("WD_REQS"))
+            .unwrap_or_else(|| Map::new(&env));
+        let request = requests.get(id).ok_or(Error::TransactionNotFound)?;
+        if caller != request.member && !Self::has_owner_rights(&env, &caller) {
+            return Err(Error::Unauthorized);
         }
+        requests.remove(id);
+
+        Self::extend_instance_ttl(&env);
         env.storage()
             .instance()
-            .set(&symbol_short!("MEMBERS"), &members_map);
-        Self::update_storage_stats(&env);
-        count
+            .set(&symbol_short!("WD_REQS"), &requests);
+
+        env.events().publish(
+            (
+                symbol_short!("withdraw"),
+                symbol_short!("cancelled"),
+                request.member,
+            ),
+            (id, caller),
+        );
+
+        Ok(true)
     }
 
-    pub fn get_access_audit(env: Env, limit: u32) -> Vec<AccessAuditEntry> {
-        let entries: Vec<AccessAuditEntry> = env
-            .storage()
+    /// Carry out a queued withdrawal once its `executable_at` has passed.
+    /// The spend is checked and recorded exactly like `record_spend`; on
+    /// failure the request stays queued.
+    pub fn execute_withdrawal(env: Env, member_address: Address, id: u64) -> Result<bool, Error> {
+        member_address.require_auth();
+        Self::settle_withdrawal(&env, &member_address, id)
+    }
+
+    /// `execute_withdrawal` signed by one of the member's delegates (see
+    /// `add_delegate`) instead of the member.
+    pub fn execute_withdrawal_as_delegate(
+        env: Env,
+        delegate: Address,
+        member_address: Address,
+        id: u64,
+    ) -> Result<bool, Error> {
+        Self::require_delegate(&env, &delegate, &member_address)?;
+        Self::settle_withdrawal(&env, &member_address, id)
+    }
+
+    pub fn get_withdrawal_request(env: Env, id: u64) -> Option<WithdrawalRequest> {
+        env.storage()
             .instance()
-            .get(&symbol_short!("ACC_AUDIT"))
-            .unwrap_or_else(|| Vec::new(&env));
-        let n = entries.len().min(limit);
-        let mut out = Vec::new(&env);
-        for i in (entries.len().saturating_sub(n))..entries.len() {
-            if let Some(e) = entries.get(i) {
-                out.push_back(e);
-            }
+            .get::<_, Map<u64, WithdrawalRequest>>(&symbol_short!("WD_REQS"))
+            .and_then(|requests| requests.get(id))
+    }
+
+    /// Seconds a `request_withdrawal` must wait before it can be executed.
+    /// Applies to requests made after the change.
+    pub fn set_withdrawal_delay(env: Env, owner: Address, delay_secs: u64) -> Result<bool, Error> {
+        owner.require_auth();
+        if !Self::has_owner_rights(&env, &owner) {
+            return Err(Error::Unauthorized);
         }
-        out
+        Self::require_not_paused(&env);
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("WD_DELAY"), &delay_secs);
+
+        Ok(true)
     }
 
-    // -----------------------------------------------------------------------
-    // Internal helpers
-    // -----------------------------------------------------------------------
+    pub fn get_withdrawal_delay(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("WD_DELAY"))
+            .unwrap_or(0)
+    }
 
-    fn execute_emergency_transfer_now(
+    /// Require `approver`'s signature on `member_address`'s multisig
+    /// proposals, such as large withdrawals. The approver may sign even if
+    /// they are not a configured signer, and their signature counts towards
+    /// the quorum. `None` removes the requirement.
+    pub fn set_approver(
         env: Env,
-        proposer: Address,
-        token: Address,
-        recipient: Address,
-        amount: i128,
-    ) -> u64 {
-        let config: EmergencyConfig = env
+        owner: Address,
+        member_address: Address,
+        approver: Option<Address>,
+    ) -> Result<bool, Error> {
+        owner.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::has_owner_rights(&env, &owner) {
+            return Err(Error::Unauthorized);
+        }
+
+        let mut members: Map<Address, FamilyMember> = env
             .storage()
             .instance()
-            .get(&symbol_short!("EM_CONF"))
-            .expect("Emergency config not set");
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        let mut record = members
+            .get(member_address.clone())
+            .ok_or(Error::MemberNotFound)?;
+        if let Some(approver) = &approver {
+            if *approver == member_address {
+                return Err(Error::InvalidSigner);
+            }
+            if !members.contains_key(approver.clone()) {
+                return Err(Error::MemberNotFound);
+            }
+        }
+        record.approver = approver;
+        members.set(member_address, record);
 
-        if amount > config.max_amount {
-            panic!("Emergency amount exceeds maximum allowed");
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members);
+
+        Ok(true)
+    }
+
+    /// End `member_address`'s ability to spend after the ledger timestamp
+    /// `expires_at` (e.g. a summer-only budget). `0` removes the expiry.
+    /// The owner's own record cannot expire.
+    pub fn set_member_expiry(
+        env: Env,
+        owner: Address,
+        member_address: Address,
+        expires_at: u64,
+    ) -> Result<bool, Error> {
+        owner.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::has_owner_rights(&env, &owner) {
+            return Err(Error::Unauthorized);
         }
 
-        let now = env.ledger().timestamp();
-        let last_ts: u64 = env
+        let mut members: Map<Address, FamilyMember> = env
             .storage()
             .instance()
-            .get(&symbol_short!("EM_LAST"))
-            .unwrap_or(0u64);
-        if last_ts != 0 && now < last_ts.saturating_add(config.cooldown) {
-            panic!("Emergency transfer cooldown period not elapsed");
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        let mut record = members
+            .get(member_address.clone())
+            .ok_or(Error::MemberNotFound)?;
+        if record.role == FamilyRole::Owner {
+            return Err(Error::InvalidRole);
         }
+        record.limit_expires_at = if expires_at == 0 {
+            None
+        } else {
+            Some(expires_at)
+        };
+        members.set(member_address, record);
 
-        let token_client = TokenClient::new(&env, &token);
-        let current_balance = token_client.balance(&proposer);
-        if current_balance - amount < config.min_balance {
-            panic!("Emergency transfer would violate minimum balance requirement");
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members);
+
+        Ok(true)
+    }
+
+    /// Cap the number of distinct recipients `member_address` may withdraw
+    /// to per spending window (see `set_limit_period`). Repeat payments to
+    /// a recipient already paid in the window are always allowed.
+    /// `max` of 0 removes the cap.
+    pub fn set_max_recipients(
+        env: Env,
+        caller: Address,
+        member_address: Address,
+        max: u32,
+    ) -> Result<bool, Error> {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::is_owner_or_admin(&env, &caller) {
+            return Err(Error::Unauthorized);
         }
 
-        env.events().publish(
-            (symbol_short!("emerg"), EmergencyEvent::TransferInit),
-            (proposer.clone(), recipient.clone(), amount),
-        );
+        let mut members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
 
-        proposer.require_auth();
-        let _ = Self::execute_transaction_internal(
-            &env,
-            &proposer,
-            &TransactionType::EmergencyTransfer,
-            &TransactionData::EmergencyTransfer(token.clone(), recipient.clone(), amount),
-            false,
-        );
+        let mut record = members
+            .get(member_address.clone())
+            .ok_or(Error::MemberNotFound)?;
+        if !Self::can_manage_member(&members, &caller, &record) {
+            return Err(Error::Unauthorized);
+        }
+        record.max_recipients = max;
+        members.set(member_address, record);
 
-        let store_ts: u64 = if now == 0 { 1u64 } else { now };
+        Self::extend_instance_ttl(&env);
         env.storage()
             .instance()
-            .set(&symbol_short!("EM_LAST"), &store_ts);
-
-        env.events().publish(
-            (symbol_short!("emerg"), EmergencyEvent::TransferExec),
-            (proposer, recipient, amount),
-        );
+            .set(&symbol_short!("MEMBERS"), &members);
 
-        0
+        Ok(true)
     }
 
-    fn execute_transaction_internal(
-        env: &Env,
-        proposer: &Address,
-        tx_type: &TransactionType,
-        data: &TransactionData,
+    /// Cap the number of spends `member_address` may make per spending
+    /// window, regardless of amount. `max` of 0 removes the cap. Owner and
+    /// Admin spends are never capped.
+    pub fn set_max_tx_count(
+        env: Env,
+        caller: Address,
+        member_address: Address,
+        max: u32,
+    ) -> Result<bool, Error> {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::is_owner_or_admin(&env, &caller) {
+            return Err(Error::Unauthorized);
+        }
+
+        let mut members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+
+        let mut record = members
+            .get(member_address.clone())
+            .ok_or(Error::MemberNotFound)?;
+        if !Self::can_manage_member(&members, &caller, &record) {
+            return Err(Error::Unauthorized);
+        }
+        record.max_tx_count = max;
+        members.set(member_address, record);
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members);
+
+        Ok(true)
+    }
+
+    /// Grow `member_address`'s limit with age: spends are checked against
+    /// `spending_limit` plus `limit_per_year_of_age` per full year since
+    /// `birth_timestamp` (see `get_effective_limit`). The stored
+    /// `spending_limit` stays the base. A `birth_timestamp` of 0 turns
+    /// scaling off.
+    pub fn set_age_scaling(
+        env: Env,
+        caller: Address,
+        member_address: Address,
+        birth_timestamp: u64,
+        limit_per_year_of_age: i128,
+    ) -> Result<bool, Error> {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::is_owner_or_admin(&env, &caller) {
+            return Err(Error::Unauthorized);
+        }
+        if limit_per_year_of_age < 0 {
+            return Err(Error::InvalidSpendingLimit);
+        }
+
+        let mut members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+
+        let mut record = members
+            .get(member_address.clone())
+            .ok_or(Error::MemberNotFound)?;
+        if !Self::can_manage_member(&members, &caller, &record) {
+            return Err(Error::Unauthorized);
+        }
+        if birth_timestamp == 0 {
+            record.birth_timestamp = None;
+            record.limit_per_year_of_age = 0;
+        } else {
+            record.birth_timestamp = Some(birth_timestamp);
+            record.limit_per_year_of_age = limit_per_year_of_age;
+        }
+        members.set(member_address, record);
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members);
+
+        Ok(true)
+    }
+
+    /// The limit spends are checked against: `spending_limit` plus
+    /// `limit_per_year_of_age` for every full 365-day year between
+    /// `birth_timestamp` and now. `None` for unknown addresses; unlimited
+    /// limits (`0`) stay unlimited.
+    pub fn get_effective_limit(env: Env, member_address: Address) -> Option<i128> {
+        let member = Self::get_member(env.clone(), member_address)?;
+        Some(Self::effective_limit(&env, &member))
+    }
+
+    /// Spends `member_address` has made in the current window, or `None`
+    /// for unknown addresses.
+    pub fn get_tx_count(env: Env, member_address: Address) -> Option<u32> {
+        let mut member = Self::get_member(env.clone(), member_address)?;
+        Self::roll_spend_window(&mut member, env.ledger().timestamp());
+        Some(member.tx_count)
+    }
+
+    /// Prepaid-style top-up: when `enabled`, every treasury allocation to
+    /// `member_address` resets their `spent`, `spent_in_period` and
+    /// `tx_count` to zero, making the full `spending_limit` and
+    /// `max_tx_count` available again.
+    pub fn set_auto_topup_on_deposit(
+        env: Env,
+        caller: Address,
+        member_address: Address,
+        enabled: bool,
+    ) -> Result<bool, Error> {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::is_owner_or_admin(&env, &caller) {
+            return Err(Error::Unauthorized);
+        }
+
+        let mut members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+
+        let mut record = members
+            .get(member_address.clone())
+            .ok_or(Error::MemberNotFound)?;
+        if !Self::can_manage_member(&members, &caller, &record) {
+            return Err(Error::Unauthorized);
+        }
+        record.auto_topup_on_deposit = enabled;
+        members.set(member_address, record);
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members);
+
+        Ok(true)
+    }
+
+    /// Recipients `member_address` has withdrawn to in the current window.
+    pub fn get_period_recipients(env: Env, member_address: Address) -> Vec<Address> {
+        let members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| Map::new(&env));
+        let mut member = match members.get(member_address.clone()) {
+            Some(member) => member,
+            None => return Vec::new(&env),
+        };
+        Self::roll_spend_window(&mut member, env.ledger().timestamp());
+
+        env.storage()
+            .instance()
+            .get::<_, Map<Address, RecipientWindow>>(&symbol_short!("RCPT_WIN"))
+            .and_then(|windows| windows.get(member_address))
+            .filter(|window| window.period_start == member.period_start)
+            .map(|window| window.recipients)
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Give `member_address` an allowance of `amount` every `interval_secs`,
+    /// accruing from now. Replaces any existing allowance; unclaimed accrual
+    /// under the old one is dropped.
+    pub fn set_allowance(
+        env: Env,
+        owner: Address,
+        member_address: Address,
+        amount: i128,
+        interval_secs: u64,
+        cap: i128,
+    ) -> Result<bool, Error> {
+        owner.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::has_owner_rights(&env, &owner) {
+            return Err(Error::Unauthorized);
+        }
+        if amount <= 0 || interval_secs == 0 || cap < 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if !Self::is_family_member(&env, &member_address) {
+            return Err(Error::MemberNotFound);
+        }
+
+        let mut allowances: Map<Address, AllowanceConfig> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("ALLOW"))
+            .unwrap_or_else(|| Map::new(&env));
+        allowances.set(
+            member_address,
+            AllowanceConfig {
+                amount,
+                interval: interval_secs,
+                cap,
+                last_claim: env.ledger().timestamp(),
+            },
+        );
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("ALLOW"), &allowances);
+
+        Ok(true)
+    }
+
+    pub fn get_allowance(env: Env, member_address: Address) -> Option<AllowanceConfig> {
+        env.storage()
+            .instance()
+            .get::<_, Map<Address, AllowanceConfig>>(&symbol_short!("ALLOW"))
+            .and_then(|allowances| allowances.get(member_address))
+    }
+
+    /// Credit every whole interval elapsed since the last claim to the
+    /// member's balance, limited to the allowance cap, and return the
+    /// amount credited. Partial intervals carry over to the next claim.
+    pub fn claim_allowance(env: Env, member_address: Address) -> Result<i128, Error> {
+        member_address.require_auth();
+        Self::require_not_paused(&env);
+
+        let mut allowances: Map<Address, AllowanceConfig> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("ALLOW"))
+            .unwrap_or_else(|| Map::new(&env));
+        let mut allowance = allowances
+            .get(member_address.clone())
+            .ok_or(Error::AllowanceNotSet)?;
+        let mut members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        let mut member = members
+            .get(member_address.clone())
+            .ok_or(Error::MemberNotFound)?;
+
+        let now = env.ledger().timestamp();
+        let intervals = now.saturating_sub(allowance.last_claim) / allowance.interval;
+        if intervals == 0 {
+            return Ok(0);
+        }
+        let mut accrued = allowance
+            .amount
+            .checked_mul(intervals as i128)
+            .unwrap_or_else(|| panic!("Arithmetic overflow"));
+        if allowance.cap > 0 && accrued > allowance.cap {
+            accrued = allowance.cap;
+        }
+
+        allowance.last_claim += intervals * allowance.interval;
+        allowances.set(member_address.clone(), allowance);
+        member.balance = Self::add_or_panic(member.balance, accrued);
+        members.set(member_address, member);
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("ALLOW"), &allowances);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members);
+
+        Ok(accrued)
+    }
+
+    /// Record an incoming deposit of `amount` of `token`, e.g. a remittance.
+    /// With `pull`, the tokens are transferred from `from` into the wallet
+    /// and anyone may deposit. Without it, the tokens must already have
+    /// arrived by a direct transfer, and only an owner may record them.
+    /// Deposits are accepted while the wallet is paused. Returns the new
+    /// running total deposited for `token`.
+    pub fn record_deposit(
+        env: Env,
+        from: Address,
+        token: Address,
+        amount: i128,
+        pull: bool,
+    ) -> Result<i128, Error> {
+        from.require_auth();
+        Self::require_initialized(&env);
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if !pull && !Self::has_owner_rights(&env, &from) {
+            return Err(Error::Unauthorized);
+        }
+
+        let mut totals: Map<Address, i128> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("DEP_TOT"))
+            .unwrap_or_else(|| Map::new(&env));
+        let total = Self::add_or_panic(totals.get(token.clone()).unwrap_or(0), amount);
+        totals.set(token.clone(), total);
+
+        if pull {
+            TokenClient::new(&env, &token).transfer(
+                &from,
+                &env.current_contract_address(),
+                &amount,
+            );
+        }
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("DEP_TOT"), &totals);
+        env.events().publish(
+            (
+                symbol_short!("deposit"),
+                symbol_short!("received"),
+                from.clone(),
+            ),
+            DepositReceivedEvent {
+                from,
+                token,
+                amount,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+
+        Ok(total)
+    }
+
+    /// Running total recorded by `record_deposit` for `token`.
+    pub fn get_total_deposited(env: Env, token: Address) -> i128 {
+        env.storage()
+            .instance()
+            .get::<_, Map<Address, i128>>(&symbol_short!("DEP_TOT"))
+            .and_then(|totals| totals.get(token))
+            .unwrap_or(0)
+    }
+
+    /// Earmark `amount` of the wallet's `token` holdings for `member_address`
+    /// by crediting their internal balance. Fails if the wallet does not
+    /// hold enough unallocated `token`.
+    pub fn allocate_to_member(
+        env: Env,
+        owner: Address,
+        token: Address,
+        member_address: Address,
+        amount: i128,
+    ) -> Result<bool, Error> {
+        owner.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::has_owner_rights(&env, &owner) {
+            return Err(Error::Unauthorized);
+        }
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let mut members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        let mut member = members
+            .get(member_address.clone())
+            .ok_or(Error::MemberNotFound)?;
+
+        let (_, unallocated) = Self::allocation_summary(&env, &token);
+        if amount > unallocated {
+            return Err(Error::InsufficientBalance);
+        }
+
+        member.balance = Self::add_or_panic(member.balance, amount);
+        if member.auto_topup_on_deposit {
+            member.spent = 0;
+            member.spent_in_period = 0;
+            member.tx_count = 0;
+        }
+        members.set(member_address, member);
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members);
+
+        Ok(true)
+    }
+
+    /// `(allocated, unallocated)`: the sum of member balances, and what is
+    /// left of the wallet's `token` holdings after them. `unallocated` is
+    /// negative if claimed allowances have over-committed the treasury.
+    pub fn get_allocation_summary(env: Env, token: Address) -> (i128, i128) {
+        Self::allocation_summary(&env, &token)
+    }
+
+    /// Move `amount` of internal balance from `from` to `to`. A transfer to
+    /// oneself fails with `InvalidAmount`.
+    ///
+    /// If `to` is not a member and auto-enrollment is configured, `to` is
+    /// added with the configured role and limit before being credited,
+    /// validated like `add_member` (renounced wallets, active disputes and
+    /// limit bounds all reject it); otherwise the transfer fails with
+    /// `MemberNotFound`.
+    pub fn transfer_balance(
+        env: Env,
+        from: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<bool, Error> {
+        from.require_auth();
+        Self::require_not_paused(&env);
+
+        if amount <= 0 || from == to {
+            return Err(Error::InvalidAmount);
+        }
+
+        let mut members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        let mut sender = members.get(from.clone()).ok_or(Error::MemberNotFound)?;
+        if sender.frozen {
+            return Err(Error::MemberFrozen);
+        }
+        if !sender.active {
+            return Err(Error::MemberInactive);
+        }
+        if sender.balance < amount {
+            return Err(Error::InsufficientBalance);
+        }
+
+        if !members.contains_key(to.clone()) {
+            let config: AutoEnrollConfig = env
+                .storage()
+                .instance()
+                .get(&symbol_short!("AUTO_ENR"))
+                .ok_or(Error::MemberNotFound)?;
+            if Self::is_renounced(&env) {
+                return Err(Error::Unauthorized);
+            }
+            Self::require_no_dispute(&env)?;
+            let mut specs = Vec::new(&env);
+            specs.push_back(MemberSpec {
+                address: to.clone(),
+                role: config.role,
+                spending_limit: config.spending_limit,
+            });
+            Self::validate_member_specs(&env, &members, &specs)?;
+            Self::insert_member_specs(&env, &from, &mut members, specs);
+        }
+        let mut recipient = members
+            .get(to.clone())
+            .unwrap_or_else(|| panic_with_error!(&env, Error::MemberNotFound));
+
+        sender.balance -= amount;
+        members.set(from, sender);
+        recipient.balance = Self::add_or_panic(recipient.balance, amount);
+        members.set(to, recipient);
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members);
+        Self::update_storage_stats(&env);
+
+        Ok(true)
+    }
+
+    /// Enroll unknown `transfer_balance` recipients as members with the given
+    /// role and limit. `None` turns auto-enrollment off.
+    pub fn set_auto_enroll(
+        env: Env,
+        caller: Address,
+        config: Option<AutoEnrollConfig>,
+    ) -> Result<bool, Error> {
+        caller.require_auth();
+        Self::require_role_at_least(&env, &caller, FamilyRole::Owner);
+        Self::require_not_paused(&env);
+
+        Self::extend_instance_ttl(&env);
+        match config {
+            Some(config) => {
+                if config.role == FamilyRole::Owner {
+                    return Err(Error::InvalidRole);
+                }
+                if config.spending_limit < 0 {
+                    return Err(Error::InvalidSpendingLimit);
+                }
+                env.storage()
+                    .instance()
+                    .set(&symbol_short!("AUTO_ENR"), &config);
+            }
+            None => env.storage().instance().remove(&symbol_short!("AUTO_ENR")),
+        }
+
+        Ok(true)
+    }
+
+    pub fn get_auto_enroll(env: Env) -> Option<AutoEnrollConfig> {
+        env.storage().instance().get(&symbol_short!("AUTO_ENR"))
+    }
+
+    /// Effective configuration for `member_address`, or `None` for unknown
+    /// addresses.
+    pub fn get_resolved_member_config(env: Env, member_address: Address) -> Option<ResolvedConfig> {
+        let members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| Map::new(&env));
+        let member = members.get(member_address.clone())?;
+
+        let mut current = member.clone();
+        Self::roll_spend_window(&mut current, env.ledger().timestamp());
+        let role_expired = Self::role_has_expired(&env, &member_address);
+        let can_spend = member.active
+            && !member.frozen
+            && !role_expired
+            && !Self::limit_expired(&env, &member)
+            && !Self::get_global_paused(&env)
+            && !Self::in_scheduled_pause(&env);
+
+        Some(ResolvedConfig {
+            address: member_address.clone(),
+            role: member.role,
+            role_expires_at: Self::get_role_expiry(&env, &member_address),
+            role_expired,
+            unlimited: Self::is_unlimited(&member),
+            spending_limit: member.spending_limit,
+            limit_period: member.limit_period,
+            period_start: current.period_start,
+            remaining_limit: Self::remaining_limit(&env, &member),
+            max_recipients: member.max_recipients,
+            can_spend,
+        })
+    }
+
+    /// Cap total spending across all members at `limit` per 30-day month,
+    /// starting now. Up to `max_rollover` of a month's unused budget carries
+    /// into the next one. A `limit` of 0 removes the cap.
+    pub fn set_wallet_monthly_cap(
+        env: Env,
+        caller: Address,
+        limit: i128,
+        max_rollover: i128,
+    ) -> Result<bool, Error> {
+        caller.require_auth();
+        Self::require_role_at_least(&env, &caller, FamilyRole::Owner);
+        Self::require_not_paused(&env);
+
+        if limit < 0 || max_rollover < 0 {
+            return Err(Error::InvalidSpendingLimit);
+        }
+
+        Self::extend_instance_ttl(&env);
+        if limit == 0 {
+            env.storage().instance().remove(&symbol_short!("WAL_CAP"));
+        } else {
+            env.storage().instance().set(
+                &symbol_short!("WAL_CAP"),
+                &WalletMonthlyCap {
+                    limit,
+                    max_rollover,
+                    period_start: env.ledger().timestamp(),
+                    spent: 0,
+                    carried: 0,
+                },
+            );
+        }
+
+        Ok(true)
+    }
+
+    /// The wallet monthly cap as of the current ledger time, with the month
+    /// rolled forward if it has ended.
+    pub fn get_wallet_monthly_cap(env: Env) -> Option<WalletMonthlyCap> {
+        let mut cap: WalletMonthlyCap = env.storage().instance().get(&symbol_short!("WAL_CAP"))?;
+        Self::roll_wallet_cap(&mut cap, env.ledger().timestamp());
+        Some(cap)
+    }
+
+    /// Budget left in the current month, or `None` when no cap is set.
+    pub fn get_wallet_monthly_remaining(env: Env) -> Option<i128> {
+        Self::get_wallet_monthly_cap(env).map(|cap| (cap.limit + cap.carried - cap.spent).max(0))
+    }
+
+    /// Allow at most `max_spends` spends wallet-wide per `window_secs`
+    /// window starting now; further spends panic with
+    /// `"Wallet rate limit exceeded"` until the window resets.
+    /// `max_spends` of 0 removes the limit.
+    pub fn set_wallet_rate_limit(
+        env: Env,
+        caller: Address,
+        max_spends: u32,
+        window_secs: u64,
+    ) -> Result<bool, Error> {
+        caller.require_auth();
+        Self::require_role_at_least(&env, &caller, FamilyRole::Owner);
+        Self::require_not_paused(&env);
+
+        Self::extend_instance_ttl(&env);
+        if max_spends == 0 {
+            env.storage().instance().remove(&symbol_short!("WAL_RATE"));
+            return Ok(true);
+        }
+        if window_secs == 0 {
+            return Err(Error::InvalidAmount);
+        }
+        env.storage().instance().set(
+            &symbol_short!("WAL_RATE"),
+            &WalletRateLimit {
+                max_spends,
+                window_secs,
+                window_start: env.ledger().timestamp(),
+                count: 0,
+            },
+        );
+
+        Ok(true)
+    }
+
+    /// The wallet rate limit as of the current ledger time, with the window
+    /// rolled forward if it has ended.
+    pub fn get_wallet_rate_limit(env: Env) -> Option<WalletRateLimit> {
+        let mut limit: WalletRateLimit =
+            env.storage().instance().get(&symbol_short!("WAL_RATE"))?;
+        Self::roll_wallet_rate_limit(&mut limit, env.ledger().timestamp());
+        Some(limit)
+    }
+
+    /// Preview `update_spending_limit(member_address, new_limit)` without
+    /// writing anything: `(over_limit, remaining)` for the current window
+    /// as they would be under the new limit.
+    pub fn preview_limit_change(
+        env: Env,
+        member_address: Address,
+        new_limit: i128,
+    ) -> Result<(bool, i128), Error> {
+        if new_limit < 0 {
+            return Err(Error::InvalidSpendingLimit);
+        }
+        let members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        let mut member = members.get(member_address).ok_or(Error::MemberNotFound)?;
+
+        Self::roll_spend_window(&mut member, env.ledger().timestamp());
+        member.spending_limit = new_limit;
+        let over_limit = !Self::is_unlimited(&member)
+            && member.spent_in_period > Self::effective_limit(&env, &member);
+        Ok((over_limit, Self::remaining_limit(&env, &member)))
+    }
+
+    /// Amount the member can still spend in the current window, or `None`
+    /// for unknown addresses. Unlimited members report `i128::MAX`.
+    pub fn get_remaining_limit(env: Env, member_address: Address) -> Option<i128> {
+        let members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+
+        members
+            .get(member_address)
+            .map(|member| Self::remaining_limit(&env, &member))
+    }
+
+    /// Members first added strictly after `timestamp`, for indexers syncing
+    /// incrementally.
+    pub fn get_members_added_after(env: Env, timestamp: u64) -> Vec<FamilyMember> {
+        let members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+
+        let mut result = Vec::new(&env);
+        for member in members.values().iter() {
+            if member.added_at > timestamp {
+                result.push_back(member);
+            }
+        }
+        result
+    }
+
+    /// Dry run of `record_spend(member_address, amount)` against the
+    /// member's own rules: the remaining limit after the spend, or `None`
+    /// if the member is missing or the spend would be refused. Writes
+    /// nothing; wallet-wide caps and pauses are not considered. Unlimited
+    /// members report `i128::MAX`.
+    pub fn simulate_spend(env: Env, member_address: Address, amount: i128) -> Option<i128> {
+        if amount <= 0 {
+            return None;
+        }
+        let members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        let mut member = members.get(member_address)?;
+        if !Self::is_unlimited(&member) && amount > Self::remaining_limit(&env, &member) {
+            return None;
+        }
+
+        Self::apply_spend(&env, &mut member, amount).ok()?;
+        Some(Self::remaining_limit(&env, &member))
+    }
+
+    /// Keep spend history records for `retention_secs` seconds. `0` keeps
+    /// history indefinitely.
+    pub fn set_history_retention(env: Env, caller: Address, retention_secs: u64) -> bool {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::is_owner_or_admin(&env, &caller) {
+            panic!("Only Owner or Admin can configure history retention");
+        }
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("HIST_RET"), &retention_secs);
+
+        true
+    }
+
+    pub fn get_history_retention(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("HIST_RET"))
+            .unwrap_or(0)
+    }
+
+    /// Drop a member's spend records older than the retention window.
+    /// Returns the number of records removed.
+    pub fn prune_history(env: Env, caller: Address, member_address: Address) -> u32 {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::is_owner_or_admin(&env, &caller) {
+            panic!("Only Owner or Admin can prune history");
+        }
+
+        let mut histories: Map<Address, Vec<SpendRecord>> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("SPND_HIST"))
+            .unwrap_or_else(|| Map::new(&env));
+        let records = match histories.get(member_address.clone()) {
+            Some(records) => records,
+            None => return 0,
+        };
+
+        let kept = Self::retained_spend_records(&env, &records);
+        let removed = records.len() - kept.len();
+        if removed > 0 {
+            histories.set(member_address, kept);
+            Self::extend_instance_ttl(&env);
+            env.storage()
+                .instance()
+                .set(&symbol_short!("SPND_HIST"), &histories);
+        }
+        removed
+    }
+
+    /// Recorded spends for a member, oldest first.
+    pub fn get_spend_history(env: Env, member_address: Address) -> Vec<SpendRecord> {
+        env.storage()
+            .instance()
+            .get::<_, Map<Address, Vec<SpendRecord>>>(&symbol_short!("SPND_HIST"))
+            .unwrap_or_else(|| Map::new(&env))
+            .get(member_address)
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Spend totals for the member's last `periods` spending windows (at most
+    /// 12), oldest first and ending with the current window. Members on a
+    /// `Lifetime` limit are bucketed by day. Unknown members get an empty
+    /// series.
+    pub fn get_spend_trend(env: Env, member_address: Address, periods: u32) -> Vec<i128> {
+        let mut series = Vec::new(&env);
+        let members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| Map::new(&env));
+        let member = match members.get(member_address.clone()) {
+            Some(member) => member,
+            None => return series,
+        };
+
+        let (current, length) = Self::spend_bucket(&member, env.ledger().timestamp());
+        let buckets: Map<u64, i128> = env
+            .storage()
+            .instance()
+            .get::<_, Map<Address, Map<u64, i128>>>(&symbol_short!("SPND_BKT"))
+            .and_then(|all| all.get(member_address))
+            .unwrap_or_else(|| Map::new(&env));
+
+        for back in (0..periods.min(MAX_TREND_PERIODS) as u64).rev() {
+            let total = current
+                .checked_sub(back * length)
+                .and_then(|start| buckets.get(start))
+                .unwrap_or(0);
+            series.push_back(total);
+        }
+        series
+    }
+
+    /// Spending limit changes for a member, oldest first. Only the last 20
+    /// changes are kept.
+    pub fn get_limit_history(env: Env, member_address: Address) -> Vec<LimitChange> {
+        env.storage()
+            .instance()
+            .get::<_, Map<Address, Vec<LimitChange>>>(&symbol_short!("LIM_HIST"))
+            .and_then(|histories| histories.get(member_address))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Everything recorded about one member in chronological order: creation,
+    /// limit changes, freezes/unfreezes and spends. Built from the limit
+    /// history, access audit and spend history, so it is only as deep as
+    /// their retention; entries sharing a timestamp keep that source order.
+    /// Unknown members get an empty list.
+    pub fn get_member_audit(env: Env, member_address: Address) -> Vec<MemberAuditEntry> {
+        let mut trail = Vec::new(&env);
+        let members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| Map::new(&env));
+        let member = match members.get(member_address.clone()) {
+            Some(member) => member,
+            None => return trail,
+        };
+
+        // The creation limit is the oldest recorded change's `old_limit`, or
+        // the current limit if it has never changed.
+        let limit_history = Self::get_limit_history(env.clone(), member_address.clone());
+        let initial_limit = limit_history
+            .first()
+            .map(|change| change.old_limit)
+            .unwrap_or(member.spending_limit);
+        Self::insert_audit_entry(
+            &mut trail,
+            MemberAuditEntry {
+                kind: MemberAuditKind::Created,
+                timestamp: member.added_at,
+                value: initial_limit,
+                previous: 0,
+            },
+        );
+        for change in limit_history.iter() {
+            Self::insert_audit_entry(
+                &mut trail,
+                MemberAuditEntry {
+                    kind: MemberAuditKind::LimitChanged,
+                    timestamp: change.timestamp,
+                    value: change.new_limit,
+                    previous: change.old_limit,
+                },
+            );
+        }
+
+        let access: Vec<AccessAuditEntry> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("ACC_AUDIT"))
+            .unwrap_or_else(|| Vec::new(&env));
+        for entry in access.iter() {
+            if !entry.success || entry.target.as_ref() != Some(&member_address) {
+                continue;
+            }
+            let kind = if entry.operation == symbol_short!("frozen") {
+                MemberAuditKind::Frozen
+            } else if entry.operation == symbol_short!("unfrozen") {
+                MemberAuditKind::Unfrozen
+            } else {
+                continue;
+            };
+            Self::insert_audit_entry(
+                &mut trail,
+                MemberAuditEntry {
+                    kind,
+                    timestamp: entry.timestamp,
+                    value: 0,
+                    previous: 0,
+                },
+            );
+        }
+
+        for spend in Self::get_spend_history(env.clone(), member_address).iter() {
+            Self::insert_audit_entry(
+                &mut trail,
+                MemberAuditEntry {
+                    kind: MemberAuditKind::Spend,
+                    timestamp: spend.timestamp,
+                    value: spend.amount,
+                    previous: 0,
+                },
+            );
+        }
+
+        trail
+    }
+
+    /// 1-based rank of a member by lifetime `spent` (1 = highest spender).
+    /// Ties are broken by address ordering so ranks are always distinct.
+    pub fn get_member_rank(env: Env, member_address: Address) -> Result<u32, Error> {
+        let members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+
+        let target = members
+            .get(member_address.clone())
+            .ok_or(Error::MemberNotFound)?;
+
+        let mut rank = 1u32;
+        for (address, member) in members.iter() {
+            if member.spent > target.spent
+                || (member.spent == target.spent && address < member_address)
+            {
+                rank += 1;
+            }
+        }
+        Ok(rank)
+    }
+
+    /// Split members into `(under, at, over)` their spending limit for the
+    /// current window. Unlimited members are always counted as under.
+    pub fn categorize_members(env: Env) -> (Vec<Address>, Vec<Address>, Vec<Address>) {
+        let members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+
+        let now = env.ledger().timestamp();
+        let mut under = Vec::new(&env);
+        let mut at = Vec::new(&env);
+        let mut over = Vec::new(&env);
+        for (address, mut member) in members.iter() {
+            if Self::is_unlimited(&member) {
+                under.push_back(address);
+                continue;
+            }
+            Self::roll_spend_window(&mut member, now);
+            let limit = Self::effective_limit(&env, &member);
+            if member.spent_in_period < limit {
+                under.push_back(address);
+            } else if member.spent_in_period == limit {
+                at.push_back(address);
+            } else {
+                over.push_back(address);
+            }
+        }
+        (under, at, over)
+    }
+
+    /// Pause or resume a member's ability to spend without removing them or
+    /// losing their configured limits. The owner cannot be deactivated.
+    pub fn set_member_active(
+        env: Env,
+        caller: Address,
+        member_address: Address,
+        active: bool,
+    ) -> Result<bool, Error> {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::is_owner_or_admin(&env, &caller) {
+            return Err(Error::Unauthorized);
+        }
+
+        let mut members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+
+        let mut record = members
+            .get(member_address.clone())
+            .ok_or(Error::MemberNotFound)?;
+        if record.role == FamilyRole::Owner {
+            return Err(Error::InvalidRole);
+        }
+        if !Self::can_manage_member(&members, &caller, &record) {
+            return Err(Error::Unauthorized);
+        }
+
+        record.active = active;
+        members.set(member_address.clone(), record);
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members);
+
+        env.events().publish(
+            (
+                symbol_short!("updated"),
+                symbol_short!("status"),
+                member_address.clone(),
+            ),
+            MemberStatusChangedEvent {
+                member: member_address,
+                active,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+
+        Ok(true)
+    }
+
+    /// Every member record paired with its `(frozen, active)` flags, for
+    /// status grids that would otherwise look members up one by one.
+    pub fn get_members_with_flags(env: Env) -> Vec<(FamilyMember, bool, bool)> {
+        let members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| Map::new(&env));
+
+        let mut result = Vec::new(&env);
+        for (_, member) in members.iter() {
+            let (frozen, active) = (member.frozen, member.active);
+            result.push_back((member, frozen, active));
+        }
+        result
+    }
+
+    /// Number of members whose spending is currently frozen.
+    pub fn get_frozen_count(env: Env) -> u32 {
+        let members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| Map::new(&env));
+
+        let mut count = 0u32;
+        for (_, member) in members.iter() {
+            if member.frozen {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    pub fn configure_multisig(
+        env: Env,
+        caller: Address,
+        tx_type: TransactionType,
+        threshold: u32,
+        signers: Vec<Address>,
+        spending_limit: i128,
+    ) -> bool {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+
+        let members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+
+        if !Self::is_owner_or_admin_in_members(&members, &caller) {
+            panic!("Only Owner or Admin can configure multi-sig");
+        }
+
+        // Validate threshold
+        let signer_count = signers.len();
+        if threshold == 0 || threshold > signer_count {
+            panic!("Invalid threshold");
+        }
+
+        for signer in signers.iter() {
+            if members.get(signer.clone()).is_none() {
+                panic!("Signer must be a family member");
+            }
+        }
+
+        if spending_limit < 0 {
+            panic!("Spending limit must be non-negative");
+        }
+
+        Self::extend_instance_ttl(&env);
+
+        let config = MultiSigConfig {
+            threshold,
+            signers,
+            spending_limit,
+        };
+
+        env.storage()
+            .instance()
+            .set(&Self::get_config_key(tx_type), &config);
+
+        true
+    }
+
+    /// Scale the withdrawal approval quorum with the amount. Tiers must be
+    /// sorted by strictly increasing `min_amount`; an empty list restores the
+    /// per-type multisig threshold.
+    pub fn set_approval_tiers(env: Env, caller: Address, tiers: Vec<ApprovalTier>) -> bool {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::is_owner_or_admin(&env, &caller) {
+            panic!("Only Owner or Admin can configure approval tiers");
+        }
+
+        let mut previous: Option<i128> = None;
+        for tier in tiers.iter() {
+            if tier.min_amount < 0 || tier.required_approvals == 0 {
+                panic!("Invalid approval tier");
+            }
+            if let Some(prev) = previous {
+                if tier.min_amount <= prev {
+                    panic!("Approval tiers must be sorted by amount");
+                }
+            }
+            previous = Some(tier.min_amount);
+        }
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("APPR_TIER"), &tiers);
+
+        true
+    }
+
+    pub fn get_approval_tiers(env: Env) -> Vec<ApprovalTier> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("APPR_TIER"))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Number of signatures a multisig withdrawal of `amount` needs.
+    pub fn get_required_approvals(env: Env, amount: i128) -> u32 {
+        let config: MultiSigConfig = env
+            .storage()
+            .instance()
+            .get(&Self::get_config_key(TransactionType::LargeWithdrawal))
+            .expect("Multi-sig config not found");
+        let data = TransactionData::Withdrawal(
+            env.current_contract_address(),
+            env.current_contract_address(),
+            amount,
+        );
+        Self::required_approvals(&env, &config, &data)
+    }
+
+    pub fn propose_transaction(
+        env: Env,
+        proposer: Address,
+        tx_type: TransactionType,
+        data: TransactionData,
+    ) -> u64 {
+        proposer.require_auth();
+        Self::require_not_paused(&env);
+        Self::require_role_at_least(&env, &proposer, FamilyRole::Member);
+
+        if !Self::is_family_member(&env, &proposer) {
+            panic!("Only family members can propose transactions");
+        }
+
+        let config_key = match tx_type {
+            TransactionType::RegularWithdrawal => {
+                Self::get_config_key(TransactionType::LargeWithdrawal)
+            }
+            _ => Self::get_config_key(tx_type),
+        };
+
+        let config: MultiSigConfig = env
+            .storage()
+            .instance()
+            .get(&config_key)
+            .expect("Multi-sig config not found");
+
+        let requires_multisig = match (&tx_type, &data) {
+            (TransactionType::RegularWithdrawal, TransactionData::Withdrawal(_, _, amount)) => {
+                *amount > config.spending_limit
+            }
+            (TransactionType::LargeWithdrawal, _) => true,
+            (TransactionType::RegularWithdrawal, _) => false,
+            _ => true,
+        };
+
+        // Only configured signers count towards the quorum, so a proposer
+        // outside the signer list still needs the full set of approvals.
+        let proposer_signs = config.signers.contains(&proposer);
+        let required = Self::required_approvals(&env, &config, &data);
+        let has_approver = Self::member_approver(&env, &proposer).is_some();
+        if !requires_multisig || (proposer_signs && required <= 1 && !has_approver) {
+            return Self::execute_transaction_internal(&env, &proposer, &tx_type, &data, false);
+        }
+
+        Self::extend_instance_ttl(&env);
+
+        let mut next_tx_id: u64 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("NEXT_TX"))
+            .unwrap_or(1);
+
+        let tx_id = next_tx_id;
+        next_tx_id += 1;
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("NEXT_TX"), &next_tx_id);
+
+        let timestamp = env.ledger().timestamp();
+        let mut signatures = Vec::new(&env);
+        if proposer_signs {
+            signatures.push_back(proposer.clone());
+        }
+
+        let pending_tx = PendingTransaction {
+            tx_id,
+            tx_type,
+            proposer: proposer.clone(),
+            signatures,
+            created_at: timestamp,
+            expires_at: timestamp + SIGNATURE_EXPIRATION,
+            data: data.clone(),
+        };
+
+        let mut pending_txs: Map<u64, PendingTransaction> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("PEND_TXS"))
+            .expect("Pending transactions map not initialized");
+
+        pending_txs.set(tx_id, pending_tx);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("PEND_TXS"), &pending_txs);
+
+        tx_id
+    }
+
+    pub fn sign_transaction(env: Env, signer: Address, tx_id: u64) -> bool {
+        signer.require_auth();
+        Self::require_not_paused(&env);
+        Self::require_role_at_least(&env, &signer, FamilyRole::Member);
+
+        if !Self::is_family_member(&env, &signer) {
+            panic!("Only family members can sign transactions");
+        }
+
+        Self::extend_instance_ttl(&env);
+
+        let mut pending_txs: Map<u64, PendingTransaction> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("PEND_TXS"))
+            .expect("Pending transactions map not initialized");
+
+        let mut pending_tx = pending_txs.get(tx_id).expect("Transaction not found");
+
+        let current_time = env.ledger().timestamp();
+        if current_time > pending_tx.expires_at {
+            panic!("Transaction expired");
+        }
+
+        for sig in pending_tx.signatures.iter() {
+            if sig.clone() == signer {
+                panic!("Already signed this transaction");
+            }
+        }
+
+        let config: MultiSigConfig = env
+            .storage()
+            .instance()
+            .get(&Self::get_config_key(pending_tx.tx_type))
+            .expect("Multi-sig config not found");
+
+        let approver = Self::member_approver(&env, &pending_tx.proposer);
+        let mut is_authorized = approver.as_ref() == Some(&signer);
+        for authorized_signer in config.signers.iter() {
+            if authorized_signer.clone() == signer {
+                is_authorized = true;
+                break;
+            }
+        }
+
+        if !is_authorized {
+            panic!("Signer not authorized for this transaction type");
+        }
+
+        pending_tx.signatures.push_back(signer.clone());
+
+        let approver_signed = match &approver {
+            Some(approver) => pending_tx.signatures.contains(approver),
+            None => true,
+        };
+        if approver_signed
+            && pending_tx.signatures.len()
+                >= Self::required_approvals(&env, &config, &pending_tx.data)
+        {
+            let executed = Self::execute_transaction_internal(
+                &env,
+                &pending_tx.proposer,
+                &pending_tx.tx_type,
+                &pending_tx.data,
+                true,
+            );
+
+            if executed == 0 {
+                pending_txs.remove(tx_id);
+                env.storage()
+                    .instance()
+                    .set(&symbol_short!("PEND_TXS"), &pending_txs);
+
+                let mut executed_txs: Map<u64, bool> = env
+                    .storage()
+                    .instance()
+                    .get(&symbol_short!("EXEC_TXS"))
+                    .expect("Executed transactions map not initialized");
+
+                executed_txs.set(tx_id, true);
+                env.storage()
+                    .instance()
+                    .set(&symbol_short!("EXEC_TXS"), &executed_txs);
+            }
+
+            return true;
+        }
+
+        pending_txs.set(tx_id, pending_tx);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("PEND_TXS"), &pending_txs);
+
+        true
+    }
+
+    pub fn withdraw(
+        env: Env,
+        proposer: Address,
+        token: Address,
+        recipient: Address,
+        amount: i128,
+    ) -> u64 {
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        Self::require_no_scheduled_pause(&env);
+
+        let config: MultiSigConfig = env
+            .storage()
+            .instance()
+            .get(&Self::get_config_key(TransactionType::LargeWithdrawal))
+            .expect("Multi-sig config not found");
+
+        let tx_type = if amount > config.spending_limit {
+            TransactionType::LargeWithdrawal
+        } else {
+            TransactionType::RegularWithdrawal
+        };
+
+        Self::propose_transaction(
+            env,
+            proposer,
+            tx_type,
+            TransactionData::Withdrawal(token, recipient, amount),
+        )
+    }
+
+    pub fn propose_split_config_change(
+        env: Env,
+        proposer: Address,
+        spending_percent: u32,
+        savings_percent: u32,
+        bills_percent: u32,
+        insurance_percent: u32,
+    ) -> u64 {
+        if spending_percent + savings_percent + bills_percent + insurance_percent != 100 {
+            panic!("Percentages must sum to 100");
+        }
+
+        Self::propose_transaction(
+            env,
+            proposer,
+            TransactionType::SplitConfigChange,
+            TransactionData::SplitConfigChange(
+                spending_percent,
+                savings_percent,
+                bills_percent,
+                insurance_percent,
+            ),
+        )
+    }
+
+    pub fn propose_role_change(
+        env: Env,
+        proposer: Address,
+        member: Address,
+        new_role: FamilyRole,
+    ) -> u64 {
+        Self::propose_transaction(
+            env,
+            proposer,
+            TransactionType::RoleChange,
+            TransactionData::RoleChange(member, new_role),
+        )
+    }
+
+    /// Change only `member_address`'s role, keeping their limit and every
+    /// other setting, without going through the `RoleChange` multisig.
+    /// Owner only. Returns `false` for non-members.
+    pub fn update_member_role(
+        env: Env,
+        owner: Address,
+        member_address: Address,
+        new_role: FamilyRole,
+    ) -> bool {
+        owner.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::has_owner_rights(&env, &owner) {
+            panic!("Only Owner can change member roles");
+        }
+        if new_role == FamilyRole::Owner {
+            panic!("Cannot assign Owner role; use add_admin or ownership transfer");
+        }
+        if Self::has_owner_rights(&env, &member_address) {
+            panic!("Cannot change owner role");
+        }
+
+        let mut members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        let mut record = match members.get(member_address.clone()) {
+            Some(record) => record,
+            None => return false,
+        };
+        record.role = new_role;
+        members.set(member_address.clone(), record);
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members);
+        Self::append_access_audit(
+            &env,
+            symbol_short!("role_chg"),
+            &owner,
+            Some(member_address),
+            true,
+        );
+
+        true
+    }
+
+    pub fn propose_emergency_transfer(
+        env: Env,
+        proposer: Address,
+        token: Address,
+        recipient: Address,
+        amount: i128,
+    ) -> u64 {
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+
+        let em_mode: bool = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("EM_MODE"))
+            .unwrap_or(false);
+
+        if em_mode {
+            return Self::execute_emergency_transfer_now(env, proposer, token, recipient, amount);
+        }
+
+        Self::propose_transaction(
+            env,
+            proposer,
+            TransactionType::EmergencyTransfer,
+            TransactionData::EmergencyTransfer(token, recipient, amount),
+        )
+    }
+
+    pub fn propose_policy_cancellation(env: Env, proposer: Address, policy_id: u32) -> u64 {
+        Self::propose_transaction(
+            env,
+            proposer,
+            TransactionType::PolicyCancellation,
+            TransactionData::PolicyCancellation(policy_id),
+        )
+    }
+
+    pub fn configure_emergency(
+        env: Env,
+        caller: Address,
+        max_amount: i128,
+        cooldown: u64,
+        min_balance: i128,
+    ) -> bool {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::is_owner_or_admin(&env, &caller) {
+            panic!("Only Owner or Admin can configure emergency settings");
+        }
+        if max_amount <= 0 {
+            panic!("Emergency max amount must be positive");
+        }
+        if min_balance < 0 {
+            panic!("Emergency min balance must be non-negative");
+        }
+
+        Self::extend_instance_ttl(&env);
+
+        env.storage().instance().set(
+            &symbol_short!("EM_CONF"),
+            &EmergencyConfig {
+                max_amount,
+                cooldown,
+                min_balance,
+            },
+        );
+
+        true
+    }
+
+    pub fn set_emergency_mode(env: Env, caller: Address, enabled: bool) -> bool {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::is_owner_or_admin(&env, &caller) {
+            panic!("Only Owner or Admin can change emergency mode");
+        }
+
+        Self::extend_instance_ttl(&env);
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("EM_MODE"), &enabled);
+
+        let event = if enabled {
+            EmergencyEvent::ModeOn
+        } else {
+            EmergencyEvent::ModeOff
+        };
+        env.events()
+            .publish((symbol_short!("emerg"), event), caller);
+
+        true
+    }
+
+    /// Sweep the wallet contract's entire `token` balance to `destination`,
+    /// e.g. when a key compromise is suspected. Owner only; bypasses member
+    /// limits, the emergency config and the pause switch. Returns the amount
+    /// moved (`0` when there was nothing to move).
+    pub fn emergency_withdraw(
+        env: Env,
+        owner: Address,
+        token: Address,
+        destination: Address,
+    ) -> Result<i128, Error> {
+        owner.require_auth();
+        if !Self::has_owner_rights(&env, &owner) {
+            return Err(Error::Unauthorized);
+        }
+
+        let token_client = TokenClient::new(&env, &token);
+        let contract = env.current_contract_address();
+        let amount = token_client.balance(&contract);
+        if amount <= 0 {
+            return Ok(0);
+        }
+
+        Self::extend_instance_ttl(&env);
+        token_client.transfer(&contract, &destination, &amount);
+        Self::append_access_audit(&env, symbol_short!("em_wdraw"), &owner, None, true);
+        env.events().publish(
+            (symbol_short!("emerg"), EmergencyEvent::Withdrawal),
+            (owner, token, destination, amount),
+        );
+
+        Ok(amount)
+    }
+
+    pub fn add_family_member(env: Env, caller: Address, member: Address, role: FamilyRole) -> bool {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+        if role == FamilyRole::Owner {
+            panic!("Cannot add Owner via add_family_member");
+        }
+        if !Self::is_owner_or_admin(&env, &caller) {
+            panic!("Only Owner or Admin can add family members");
+        }
+        if Self::is_renounced(&env) {
+            panic!("Ownership has been renounced");
+        }
+        if Self::has_owner_rights(&env, &member) {
+            panic!("Owner cannot be a limited member");
+        }
+
+        Self::extend_instance_ttl(&env);
+
+        let mut members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+
+        let timestamp = env.ledger().timestamp();
+        let record = Self::readd_member(&members, member.clone(), role, timestamp);
+        members.set(member.clone(), record);
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members);
+
+        Self::append_access_audit(
+            &env,
+            symbol_short!("add_mem"),
+            &caller,
+            Some(member.clone()),
+            true,
+        );
+        env.events().publish(
+            (
+                symbol_short!("added"),
+                symbol_short!("member"),
+                member.clone(),
+            ),
+            MemberAddedEvent {
+                member,
+                role,
+                spending_limit: 0,
+                timestamp,
+            },
+        );
+        true
+    }
+
+    pub fn remove_family_member(env: Env, caller: Address, member: Address) -> bool {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::has_owner_rights(&env, &caller) {
+            panic!("Only Owner can remove family members");
+        }
+        if Self::has_owner_rights(&env, &member) {
+            panic!("Cannot remove owner");
+        }
+
+        Self::extend_instance_ttl(&env);
+
+        let mut members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+
+        members.remove(member.clone());
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members);
+        Self::drop_delegates(&env, &member);
+
+        Self::append_access_audit(&env, symbol_short!("rem_mem"), &caller, Some(member), true);
+        true
+    }
+
+    pub fn get_pending_transaction(env: Env, tx_id: u64) -> Option<PendingTransaction> {
+        let pending_txs: Map<u64, PendingTransaction> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("PEND_TXS"))
+            .expect("Pending transactions map not initialized");
+
+        pending_txs.get(tx_id)
+    }
+
+    /// Everything awaiting action in one list: the pending ownership
+    /// transfer (if any) first, then pending multisig transactions in
+    /// `tx_id` order, then queued withdrawal requests in id order.
+    pub fn get_pending_operations(env: Env) -> Vec<PendingOp> {
+        let mut ops = Vec::new(&env);
+
+        if let Some(pending) = env
+            .storage()
+            .instance()
+            .get::<_, PendingOwnership>(&symbol_short!("PEND_OWN"))
+        {
+            let delay: u64 = env
+                .storage()
+                .instance()
+                .get(&symbol_short!("OWN_DLY"))
+                .unwrap_or(0);
+            ops.push_back(PendingOp {
+                kind: PendingOpKind::OwnershipTransfer,
+                id: 0,
+                subject: pending.new_owner,
+                created_at: pending.proposed_at,
+                effective_at: pending.proposed_at.saturating_add(delay),
+                expires_at: 0,
+            });
+        }
+
+        let pending_txs: Map<u64, PendingTransaction> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("PEND_TXS"))
+            .unwrap_or_else(|| Map::new(&env));
+        for (tx_id, tx) in pending_txs.iter() {
+            ops.push_back(PendingOp {
+                kind: PendingOpKind::Transaction,
+                id: tx_id,
+                subject: tx.proposer,
+                created_at: tx.created_at,
+                effective_at: tx.created_at,
+                expires_at: tx.expires_at,
+            });
+        }
+
+        let requests: Map<u64, WithdrawalRequest> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("WD_REQS"))
+            .unwrap_or_else(|| Map::new(&env));
+        for (id, request) in requests.iter() {
+            ops.push_back(PendingOp {
+                kind: PendingOpKind::WithdrawalRequest,
+                id,
+                subject: request.member,
+                created_at: request.requested_at,
+                effective_at: request.executable_at,
+                expires_at: 0,
+            });
+        }
+
+        ops
+    }
+
+    pub fn get_multisig_config(env: Env, tx_type: TransactionType) -> Option<MultiSigConfig> {
+        env.storage().instance().get(&Self::get_config_key(tx_type))
+    }
+
+    pub fn get_family_member(env: Env, member: Address) -> Option<FamilyMember> {
+        Self::require_initialized(&env);
+        let members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+
+        members.get(member)
+    }
+
+    /// Number of family members, not counting `Owner`-role records (the
+    /// root owner and co-owners). `get_members_paged` pages over every
+    /// record, owners included.
+    pub fn get_member_count(env: Env) -> u32 {
+        let members: Map<Address, FamilyMember> =
+            match env.storage().instance().get(&symbol_short!("MEMBERS")) {
+                Some(members) => members,
+                None => return 0,
+            };
+        let mut count = 0u32;
+        for member in members.values().iter() {
+            if member.role != FamilyRole::Owner {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Page through member records without loading every one into the
+    /// result.
+    ///
+    /// Members are returned in `MEMBERS` map order (sorted by address),
+    /// skipping the first `start` entries and returning at most `limit`.
+    pub fn get_members_paged(env: Env, start: u32, limit: u32) -> Vec<FamilyMember> {
+        Self::require_initialized(&env);
+        let members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+
+        let mut result = Vec::new(&env);
+        for (_, member) in members.iter().skip(start as usize).take(limit as usize) {
+            result.push_back(member);
+        }
+        result
+    }
+
+    /// Every member record ordered by `spending_limit`, largest first when
+    /// `descending`. Ties keep `MEMBERS` map order in both directions.
+    pub fn get_members_sorted_by_limit(env: Env, descending: bool) -> Vec<FamilyMember> {
+        let mut sorted: Vec<FamilyMember> = Vec::new(&env);
+        let members: Map<Address, FamilyMember> =
+            match env.storage().instance().get(&symbol_short!("MEMBERS")) {
+                Some(members) => members,
+                None => return sorted,
+            };
+
+        // Insertion sort: each record goes after every entry it does not
+        // strictly precede, which keeps equal limits in map order.
+        for (_, member) in members.iter() {
+            let mut index = sorted.len();
+            while index > 0 {
+                let previous = sorted.get(index - 1).unwrap().spending_limit;
+                let precedes = if descending {
+                    member.spending_limit > previous
+                } else {
+                    member.spending_limit < previous
+                };
+                if !precedes {
+                    break;
+                }
+                index -= 1;
+            }
+            sorted.insert(index, member);
+        }
+        sorted
+    }
+
+    /// Every member address (the owner included) without the records, in
+    /// `MEMBERS` map order. Empty before `init`.
+    pub fn get_member_addresses(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get::<_, Map<Address, FamilyMember>>(&symbol_short!("MEMBERS"))
+            .map(|members| members.keys())
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Member records holding exactly `role`, in `MEMBERS` map order.
+    /// Returns an empty list when no member has the role or the wallet is
+    /// not initialized.
+    pub fn get_members_by_role(env: Env, role: FamilyRole) -> Vec<FamilyMember> {
+        let mut result = Vec::new(&env);
+        let members: Map<Address, FamilyMember> =
+            match env.storage().instance().get(&symbol_short!("MEMBERS")) {
+                Some(members) => members,
+                None => return result,
+            };
+
+        for (_, member) in members.iter() {
+            if member.role == role {
+                result.push_back(member);
+            }
+        }
+        result
+    }
+
+    /// Capped members whose current-window spend is at least `percent` of
+    /// their `spending_limit` (`spend * 100 / limit >= percent`), in
+    /// `MEMBERS` map order. Unlimited members never qualify.
+    pub fn get_members_over_utilization(env: Env, percent: u32) -> Vec<Address> {
+        let mut result = Vec::new(&env);
+        let members: Map<Address, FamilyMember> =
+            match env.storage().instance().get(&symbol_short!("MEMBERS")) {
+                Some(members) => members,
+                None => return result,
+            };
+
+        let now = env.ledger().timestamp();
+        for (address, mut member) in members.iter() {
+            if Self::is_unlimited(&member) {
+                continue;
+            }
+            Self::roll_spend_window(&mut member, now);
+            let limit = Self::effective_limit(&env, &member);
+            if Self::utilization_percent(member.spent_in_period, limit) >= i128::from(percent) {
+                result.push_back(address);
+            }
+        }
+        result
+    }
+
+    pub fn get_owner(env: Env) -> Address {
+        Self::require_initialized(&env)
+    }
+
+    /// Whether `address` holds owner authority (the root owner or a
+    /// co-owner), for contracts that integrate with the wallet. `false` on
+    /// an uninitialized wallet instead of failing.
+    pub fn is_owner(env: Env, address: Address) -> bool {
+        match env
+            .storage()
+            .instance()
+            .get::<_, Address>(&symbol_short!("OWNER"))
+        {
+            Some(owner) => owner == address || Self::co_owners(&env).contains(&address),
+            None => false,
+        }
+    }
+
+    /// Label the wallet for dashboards listing several households. Owner
+    /// only; an empty `name` removes the label.
+    pub fn set_wallet_name(env: Env, owner: Address, name: String) -> Result<bool, Error> {
+        owner.require_auth();
+        if !Self::has_owner_rights(&env, &owner) {
+            return Err(Error::Unauthorized);
+        }
+        Self::require_not_paused(&env);
+
+        Self::extend_instance_ttl(&env);
+        if name.is_empty() {
+            env.storage().instance().remove(&symbol_short!("W_NAME"));
+        } else {
+            env.storage()
+                .instance()
+                .set(&symbol_short!("W_NAME"), &name);
+        }
+
+        Ok(true)
+    }
+
+    pub fn get_wallet_name(env: Env) -> Option<String> {
+        env.storage().instance().get(&symbol_short!("W_NAME"))
+    }
+
+    /// Owner, member count, pause state and summed limits in one read.
+    pub fn get_summary(env: Env) -> WalletSummary {
+        let owner = Self::require_initialized(&env);
+        let members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| Map::new(&env));
+
+        WalletSummary {
+            owner,
+            member_count: Self::get_member_count(env.clone()),
+            paused: Self::get_global_paused(&env),
+            total_limit: Self::saturating_total(&members, |member| member.spending_limit),
+        }
+    }
+
+    /// Lifetime `spent` summed over every member, saturating at `i128::MAX`.
+    /// `0` before `init`.
+    pub fn get_total_spent(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get::<_, Map<Address, FamilyMember>>(&symbol_short!("MEMBERS"))
+            .map(|members| Self::saturating_total(&members, |member| member.spent))
+            .unwrap_or(0)
+    }
+
+    /// Stored `spending_limit` summed over every member, saturating at
+    /// `i128::MAX`. Unlimited members (limit 0) add nothing.
+    pub fn get_total_limit(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get::<_, Map<Address, FamilyMember>>(&symbol_short!("MEMBERS"))
+            .map(|members| Self::saturating_total(&members, |member| member.spending_limit))
+            .unwrap_or(0)
+    }
+
+    /// Start a two-step ownership transfer. The proposal only takes effect
+    /// once `new_owner` calls `accept_ownership`; proposing again replaces any
+    /// earlier pending proposal.
+    pub fn propose_new_owner(env: Env, current_owner: Address, new_owner: Address) -> bool {
+        current_owner.require_auth();
+        Self::require_not_paused(&env);
+
+        let owner: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("OWNER"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        if current_owner != owner {
+            panic!("Only Owner can transfer ownership");
+        }
+        if new_owner == owner {
+            panic!("New owner must differ from current owner");
+        }
+
+        Self::extend_instance_ttl(&env);
+        env.storage().instance().set(
+            &symbol_short!("PEND_OWN"),
+            &PendingOwnership {
+                new_owner: new_owner.clone(),
+                proposed_at: env.ledger().timestamp(),
+                confirmed: false,
+            },
+        );
+
+        env.events().publish(
+            (symbol_short!("owner"), OwnershipEvent::TransferProposed),
+            (current_owner, new_owner),
+        );
+
+        true
+    }
+
+    /// Require the current owner to confirm a proposed transfer a second
+    /// time, no earlier than `delay` seconds after proposing. `None` turns the
+    /// extra step off.
+    pub fn set_transfer_confirmation(env: Env, caller: Address, delay: Option<u64>) -> bool {
+        caller.require_auth();
+        Self::require_role_at_least(&env, &caller, FamilyRole::Owner);
+        Self::require_not_paused(&env);
+
+        Self::extend_instance_ttl(&env);
+        match delay {
+            Some(d) => env.storage().instance().set(&symbol_short!("OWN_DLY"), &d),
+            None => env.storage().instance().remove(&symbol_short!("OWN_DLY")),
+        }
+
+        true
+    }
+
+    pub fn get_transfer_confirmation(env: Env) -> Option<u64> {
+        env.storage().instance().get(&symbol_short!("OWN_DLY"))
+    }
+
+    /// Second-factor confirmation of a pending ownership transfer by the
+    /// current owner, allowed once the configured timelock has elapsed.
+    pub fn confirm_transfer(env: Env, current_owner: Address) -> bool {
+        current_owner.require_auth();
+        Self::require_not_paused(&env);
+
+        let owner: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("OWNER"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        if current_owner != owner {
+            panic!("Only Owner can confirm ownership transfer");
+        }
+
+        let delay: u64 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("OWN_DLY"))
+            .expect("Transfer confirmation not enabled");
+
+        let mut pending: PendingOwnership = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("PEND_OWN"))
+            .expect("No pending ownership transfer");
+        if env.ledger().timestamp() < pending.proposed_at.saturating_add(delay) {
+            panic!("Transfer confirmation timelock not elapsed");
+        }
+
+        pending.confirmed = true;
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("PEND_OWN"), &pending);
+
+        env.events().publish(
+            (symbol_short!("owner"), OwnershipEvent::TransferConfirmed),
+            (current_owner, pending.new_owner),
+        );
+
+        true
+    }
+
+    /// Complete a pending ownership transfer. The previous owner stays in the
+    /// family as an Admin.
+    pub fn accept_ownership(env: Env, new_owner: Address) -> bool {
+        new_owner.require_auth();
+        Self::require_not_paused(&env);
+
+        let pending: PendingOwnership = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("PEND_OWN"))
+            .expect("No pending ownership transfer");
+        if pending.new_owner != new_owner {
+            panic!("Caller is not the pending owner");
+        }
+        if env.storage().instance().has(&symbol_short!("OWN_DLY")) && !pending.confirmed {
+            panic!("Ownership transfer not confirmed by owner");
+        }
+
+        let previous_owner: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("OWNER"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+
+        let mut members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+
+        let now = env.ledger().timestamp();
+        if let Some(mut record) = members.get(previous_owner.clone()) {
+            record.role = FamilyRole::Admin;
+            members.set(previous_owner.clone(), record);
+        }
+        let mut record = members
+            .get(new_owner.clone())
+            .unwrap_or_else(|| Self::new_member(new_owner.clone(), FamilyRole::Owner, 0, now));
+        record.role = FamilyRole::Owner;
+        record.frozen = false;
+        record.active = true;
+        members.set(new_owner.clone(), record);
+
+        let mut co_owners = Self::co_owners(&env);
+        if let Some(index) = co_owners.first_index_of(&new_owner) {
+            co_owners.remove(index);
+            env.storage()
+                .instance()
+                .set(&symbol_short!("ADMINS"), &co_owners);
+        }
+
+        let mut expiries: Map<Address, u64> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("ROLE_EXP"))
+            .unwrap_or_else(|| Map::new(&env));
+        if expiries.contains_key(new_owner.clone()) {
+            expiries.remove(new_owner.clone());
+            env.storage()
+                .instance()
+                .set(&symbol_short!("ROLE_EXP"), &expiries);
+        }
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("OWNER"), &new_owner);
+        env.storage().instance().remove(&symbol_short!("PEND_OWN"));
+
+        env.events().publish(
+            (symbol_short!("owner"), OwnershipEvent::Transferred),
+            OwnershipTransferredEvent {
+                previous_owner,
+                new_owner,
+                timestamp: now,
+            },
+        );
+
+        true
+    }
+
+    /// Permanently give up ownership. **This cannot be undone.**
+    ///
+    /// `OWNER` is pointed at the wallet contract's own address, which can
+    /// never authorize a call, and the former owner stays on as a `Member`.
+    /// From then on every Owner-gated function reverts, and any pending
+    /// transfer is dropped. Co-owners are demoted to `Member`. Admins keep
+    /// their other powers, but no member can be added by anyone. Reads
+    /// keep working.
+    pub fn renounce_ownership(env: Env, owner: Address) -> bool {
+        owner.require_auth();
+        Self::require_not_paused(&env);
+
+        let current: Address = Self::require_initialized(&env);
+        if owner != current {
+            panic!("Only Owner can renounce ownership");
+        }
+
+        let mut members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        if let Some(mut record) = members.get(owner.clone()) {
+            record.role = FamilyRole::Member;
+            members.set(owner.clone(), record);
+        }
+        for co_owner in Self::co_owners(&env).iter() {
+            if let Some(mut record) = members.get(co_owner.clone()) {
+                record.role = FamilyRole::Member;
+                members.set(co_owner, record);
+            }
+        }
+        env.storage().instance().remove(&symbol_short!("ADMINS"));
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("OWNER"), &env.current_contract_address());
+        env.storage().instance().remove(&symbol_short!("PEND_OWN"));
+
+        env.events().publish(
+            (symbol_short!("owner"), OwnershipEvent::Renounced),
+            (owner, env.ledger().timestamp()),
+        );
+
+        true
+    }
+
+    /// Give `new_admin` equal owner rights alongside the root owner. The
+    /// member's role becomes `Owner`, so every Owner-gated call accepts them.
+    /// Transferring or renouncing ownership and managing co-owners stay with
+    /// the root owner.
+    pub fn add_admin(env: Env, owner: Address, new_admin: Address) -> Result<bool, Error> {
+        owner.require_auth();
+        Self::require_not_paused(&env);
+
+        let root = Self::require_initialized(&env);
+        if owner != root {
+            return Err(Error::Unauthorized);
+        }
+        let mut co_owners = Self::co_owners(&env);
+        if new_admin == root || co_owners.contains(&new_admin) {
+            return Err(Error::InvalidRole);
+        }
+
+        let mut members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        let mut record = members
+            .get(new_admin.clone())
+            .ok_or(Error::MemberNotFound)?;
+        record.role = FamilyRole::Owner;
+        members.set(new_admin.clone(), record);
+        co_owners.push_back(new_admin.clone());
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("ADMINS"), &co_owners);
+
+        Self::append_access_audit(
+            &env,
+            symbol_short!("add_adm"),
+            &owner,
+            Some(new_admin.clone()),
+            true,
+        );
+        env.events().publish(
+            (symbol_short!("owner"), OwnershipEvent::CoOwnerAdded),
+            new_admin,
+        );
+        Ok(true)
+    }
+
+    /// Revoke a co-owner's rights; they stay on as a plain `Member`. The root
+    /// owner cannot be removed this way.
+    pub fn remove_admin(env: Env, owner: Address, admin: Address) -> Result<bool, Error> {
+        owner.require_auth();
+        Self::require_not_paused(&env);
+
+        let root = Self::require_initialized(&env);
+        if owner != root {
+            return Err(Error::Unauthorized);
+        }
+        if admin == root {
+            return Err(Error::InvalidRole);
+        }
+        let mut co_owners = Self::co_owners(&env);
+        let index = co_owners
+            .first_index_of(&admin)
+            .ok_or(Error::MemberNotFound)?;
+        co_owners.remove(index);
+
+        let mut members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        if let Some(mut record) = members.get(admin.clone()) {
+            record.role = FamilyRole::Member;
+            members.set(admin.clone(), record);
+        }
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("ADMINS"), &co_owners);
+
+        Self::append_access_audit(
+            &env,
+            symbol_short!("rem_adm"),
+            &owner,
+            Some(admin.clone()),
+            true,
+        );
+        env.events().publish(
+            (symbol_short!("owner"), OwnershipEvent::CoOwnerRemoved),
+            admin,
+        );
+        Ok(true)
+    }
+
+    /// Co-owners added with `add_admin`, in the order they were added. The
+    /// root owner (`get_owner`) is not included.
+    pub fn get_admins(env: Env) -> Vec<Address> {
+        Self::co_owners(&env)
+    }
+
+    pub fn get_pending_owner(env: Env) -> Option<Address> {
+        Self::get_pending_ownership(env).map(|pending| pending.new_owner)
+    }
+
+    pub fn get_pending_ownership(env: Env) -> Option<PendingOwnership> {
+        env.storage().instance().get(&symbol_short!("PEND_OWN"))
+    }
+
+    pub fn get_emergency_config(env: Env) -> Option<EmergencyConfig> {
+        env.storage().instance().get(&symbol_short!("EM_CONF"))
+    }
+
+    pub fn is_emergency_mode(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("EM_MODE"))
+            .unwrap_or(false)
+    }
+
+    pub fn get_last_emergency_at(env: Env) -> Option<u64> {
+        let ts: u64 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("EM_LAST"))
+            .unwrap_or(0u64);
+        if ts == 0 {
+            None
+        } else {
+            Some(ts)
+        }
+    }
+
+    pub fn archive_old_transactions(env: Env, caller: Address, before_timestamp: u64) -> u32 {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::is_owner_or_admin(&env, &caller) {
+            panic!("Only Owner or Admin can archive transactions");
+        }
+
+        Self::extend_instance_ttl(&env);
+
+        let executed_txs: Map<u64, bool> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("EXEC_TXS"))
+            .unwrap_or_else(|| Map::new(&env));
+
+        let mut archived: Map<u64, ArchivedTransaction> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("ARCH_TX"))
+            .unwrap_or_else(|| Map::new(&env));
+
+        let current_time = env.ledger().timestamp();
+        let mut archived_count = 0u32;
+
+        for (tx_id, _) in executed_txs.iter() {
+            let archived_tx = ArchivedTransaction {
+                tx_id,
+                tx_type: TransactionType::RegularWithdrawal,
+                proposer: caller.clone(),
+                executed_at: before_timestamp,
+                archived_at: current_time,
+            };
+            archived.set(tx_id, archived_tx);
+            archived_count += 1;
+        }
+
+        if archived_count > 0 {
+            env.storage()
+                .instance()
+                .set(&symbol_short!("EXEC_TXS"), &Map::<u64, bool>::new(&env));
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("ARCH_TX"), &archived);
+
+        Self::extend_archive_ttl(&env);
+        Self::update_storage_stats(&env);
+
+        env.events().publish(
+            (symbol_short!("wallet"), ArchiveEvent::TransactionsArchived),
+            (archived_count, caller),
+        );
+
+        archived_count
+    }
+
+    pub fn get_archived_transactions(env: Env, limit: u32) -> Vec<ArchivedTransaction> {
+        let archived: Map<u64, ArchivedTransaction> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("ARCH_TX"))
+            .unwrap_or_else(|| Map::new(&env));
+
+        let mut result = Vec::new(&env);
+        for (count, (_, tx)) in archived.iter().enumerate() {
+            if count as u32 >= limit {
+                break;
+            }
+            result.push_back(tx);
+        }
+        result
+    }
+
+    pub fn cleanup_expired_pending(env: Env, caller: Address) -> u32 {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::is_owner_or_admin(&env, &caller) {
+            panic!("Only Owner or Admin can cleanup expired transactions");
+        }
+
+        Self::extend_instance_ttl(&env);
+
+        let mut pending_txs: Map<u64, PendingTransaction> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("PEND_TXS"))
+            .unwrap_or_else(|| Map::new(&env));
+
+        let current_time = env.ledger().timestamp();
+        let mut removed_count = 0u32;
+        let mut to_remove: Vec<u64> = Vec::new(&env);
+
+        for (tx_id, tx) in pending_txs.iter() {
+            if tx.expires_at < current_time {
+                to_remove.push_back(tx_id);
+                removed_count += 1;
+            }
+        }
+
+        for i in 0..to_remove.len() {
+            if let Some(id) = to_remove.get(i) {
+                pending_txs.remove(id);
+            }
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("PEND_TXS"), &pending_txs);
+
+        Self::update_storage_stats(&env);
+
+        env.events().publish(
+            (symbol_short!("wallet"), ArchiveEvent::ExpiredCleaned),
+            (removed_count, caller),
+        );
+
+        removed_count
+    }
+
+    pub fn get_storage_stats(env: Env) -> StorageStats {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("STOR_STAT"))
+            .unwrap_or(StorageStats {
+                pending_transactions: 0,
+                archived_transactions: 0,
+                total_members: 0,
+                last_updated: 0,
+            })
+    }
+
+    /// Whether member bookkeeping is consistent: every `MEMBERS` record is
+    /// stored under its own address, the root owner and each co-owner in
+    /// `ADMINS` hold an `Owner` record, and `STOR_STAT` counts every
+    /// member.
+    pub fn verify_integrity(env: Env) -> bool {
+        Self::reconcile_members(&env, false) == 0
+    }
+
+    /// Fix what `verify_integrity` reports, treating `MEMBERS` keys as
+    /// authoritative: records are re-addressed to their key, a missing
+    /// owner record is recreated, co-owners without a record are dropped
+    /// from `ADMINS` and `STOR_STAT` is recounted. Owner only. Returns the
+    /// number of problems fixed.
+    pub fn repair_integrity(env: Env, owner: Address) -> Result<u32, Error> {
+        owner.require_auth();
+        if !Self::has_owner_rights(&env, &owner) {
+            return Err(Error::Unauthorized);
+        }
+
+        Self::extend_instance_ttl(&env);
+        let fixed = Self::reconcile_members(&env, true);
+        if fixed > 0 {
+            Self::append_access_audit(&env, symbol_short!("repair"), &owner, None, true);
+        }
+        Ok(fixed)
+    }
+
+    /// Live estimate of storage usage as `(member_count, stored_entries)`.
+    ///
+    /// `stored_entries` counts members plus every record in the pending,
+    /// executed, archived, role-expiry, access-audit and spend-history
+    /// collections, which are the ones that grow with use and may need
+    /// pruning.
+    pub fn get_storage_footprint(env: Env) -> (u32, u32) {
+        let storage = env.storage().instance();
+        let members = storage
+            .get::<_, Map<Address, FamilyMember>>(&symbol_short!("MEMBERS"))
+            .map(|m| m.len())
+            .unwrap_or(0);
+        let pending = storage
+            .get::<_, Map<u64, PendingTransaction>>(&symbol_short!("PEND_TXS"))
+            .map(|m| m.len())
+            .unwrap_or(0);
+        let executed = storage
+            .get::<_, Map<u64, bool>>(&symbol_short!("EXEC_TXS"))
+            .map(|m| m.len())
+            .unwrap_or(0);
+        let archived = storage
+            .get::<_, Map<u64, ArchivedTransaction>>(&symbol_short!("ARCH_TX"))
+            .map(|m| m.len())
+            .unwrap_or(0);
+        let role_expiries = storage
+            .get::<_, Map<Address, u64>>(&symbol_short!("ROLE_EXP"))
+            .map(|m| m.len())
+            .unwrap_or(0);
+        let audit = storage
+            .get::<_, Vec<AccessAuditEntry>>(&symbol_short!("ACC_AUDIT"))
+            .map(|v| v.len())
+            .unwrap_or(0);
+        let mut spend_history = 0u32;
+        if let Some(histories) =
+            storage.get::<_, Map<Address, Vec<SpendRecord>>>(&symbol_short!("SPND_HIST"))
+        {
+            for (_, records) in histories.iter() {
+                spend_history += records.len();
+            }
+        }
+
+        (
+            members,
+            members + pending + executed + archived + role_expiries + audit + spend_history,
+        )
+    }
+
+    /// SHA-256 over the XDR encoding of the owner, every member record and
+    /// the wallet-level configuration (multisig, approval tiers, emergency
+    /// and pause settings).
+    ///
+    /// The digest only changes when that state changes, so clients can
+    /// compare two readings to detect unexpected writes.
+    pub fn state_digest(env: Env) -> BytesN<32> {
+        let storage = env.storage().instance();
+        let mut data = Bytes::new(&env);
+        data.append(
+            &storage
+                .get::<_, Address>(&symbol_short!("OWNER"))
+                .to_xdr(&env),
+        );
+        data.append(
+            &storage
+                .get::<_, Map<Address, FamilyMember>>(&symbol_short!("MEMBERS"))
+                .to_xdr(&env),
+        );
+        for tx_type in [
+            TransactionType::LargeWithdrawal,
+            TransactionType::SplitConfigChange,
+            TransactionType::RoleChange,
+            TransactionType::EmergencyTransfer,
+            TransactionType::PolicyCancellation,
+            TransactionType::RegularWithdrawal,
+        ] {
+            data.append(
+                &storage
+                    .get::<_, MultiSigConfig>(&Self::get_config_key(tx_type))
+                    .to_xdr(&env),
+            );
+        }
+        data.append(
+            &storage
+                .get::<_, Vec<ApprovalTier>>(&symbol_short!("APPR_TIER"))
+                .to_xdr(&env),
+        );
+        data.append(
+            &storage
+                .get::<_, EmergencyConfig>(&symbol_short!("EM_CONF"))
+                .to_xdr(&env),
+        );
+        data.append(
+            &storage
+                .get::<_, bool>(&symbol_short!("EM_MODE"))
+                .to_xdr(&env),
+        );
+        data.append(
+            &storage
+                .get::<_, bool>(&symbol_short!("PAUSED"))
+                .to_xdr(&env),
+        );
+        env.crypto().sha256(&data).into()
+    }
+
+    pub fn set_role_expiry(
+        env: Env,
+        caller: Address,
+        member: Address,
+        expires_at: Option<u64>,
+    ) -> bool {
+        caller.require_auth();
+        Self::require_role_at_least(&env, &caller, FamilyRole::Admin);
+        Self::require_not_paused(&env);
+        Self::extend_instance_ttl(&env);
+        let mut m: Map<Address, u64> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("ROLE_EXP"))
+            .unwrap_or_else(|| Map::new(&env));
+        match expires_at {
+            Some(t) => m.set(member.clone(), t),
+            None => {
+                m.remove(member.clone());
+            }
+        }
+        env.storage().instance().set(&symbol_short!("ROLE_EXP"), &m);
+        Self::append_access_audit(&env, symbol_short!("role_exp"), &caller, Some(member), true);
+        true
+    }
+
+    pub fn get_role_expiry_public(env: Env, address: Address) -> Option<u64> {
+        Self::get_role_expiry(&env, &address)
+    }
+
+    pub fn pause(env: Env, caller: Address) -> bool {
+        caller.require_auth();
+        Self::require_role_at_least(&env, &caller, FamilyRole::Admin);
+        let admin = Self::get_pause_admin(&env).unwrap_or_else(|| {
+            env.storage()
+                .instance()
+                .get(&symbol_short!("OWNER"))
+                .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized))
+        });
+        if admin != caller {
+            panic!("Only pause admin can pause");
+        }
+        env.storage()
+            .instance()
+            .set(&symbol_short!("PAUSED"), &true);
+        env.events()
+            .publish((symbol_short!("wallet"), symbol_short!("paused")), ());
+        true
+    }
+
+    pub fn unpause(env: Env, caller: Address) -> bool {
+        caller.require_auth();
+        let admin = Self::get_pause_admin(&env).unwrap_or_else(|| {
+            env.storage()
+                .instance()
+                .get(&symbol_short!("OWNER"))
+                .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized))
+        });
+        if admin != caller {
+            panic!("Only pause admin can unpause");
+        }
+        env.storage()
+            .instance()
+            .set(&symbol_short!("PAUSED"), &false);
+        env.events()
+            .publish((symbol_short!("wallet"), symbol_short!("unpaused")), ());
+        true
+    }
+
+    /// Freeze `add_member` and every spending-limit change for
+    /// `DISPUTE_WINDOW_SECONDS`. Only an active Guardian may flag; flagging
+    /// again restarts the window. Allowed while paused. Returns the
+    /// timestamp the dispute lapses at.
+    pub fn flag_dispute(env: Env, guardian: Address) -> Result<u64, Error> {
+        guardian.require_auth();
+        let members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        match members.get(guardian.clone()) {
+            Some(member) if member.role == FamilyRole::Guardian && member.active => {}
+            _ => return Err(Error::Unauthorized),
+        }
+
+        let until = env.ledger().timestamp() + DISPUTE_WINDOW_SECONDS;
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("DISPUTE"), &until);
+        Self::append_access_audit(&env, symbol_short!("dispute"), &guardian, None, true);
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("flagged"), guardian),
+            until,
+        );
+
+        Ok(until)
+    }
+
+    /// Lift a dispute before its window elapses. Owner only.
+    pub fn resolve_dispute(env: Env, owner: Address) -> Result<bool, Error> {
+        owner.require_auth();
+        if !Self::has_owner_rights(&env, &owner) {
+            return Err(Error::Unauthorized);
+        }
+
+        Self::extend_instance_ttl(&env);
+        env.storage().instance().remove(&symbol_short!("DISPUTE"));
+        Self::append_access_audit(&env, symbol_short!("disp_res"), &owner, None, true);
+        env.events()
+            .publish((symbol_short!("dispute"), symbol_short!("resolved")), owner);
+
+        Ok(true)
+    }
+
+    /// When the active dispute lapses, or `None` if there is none.
+    pub fn get_dispute_until(env: Env) -> Option<u64> {
+        env.storage()
+            .instance()
+            .get::<_, u64>(&symbol_short!("DISPUTE"))
+            .filter(|until| *until > env.ledger().timestamp())
+    }
+
+    /// Schedule a window during which spends and withdrawals are rejected,
+    /// without needing manual `pause`/`unpause` calls. Windows that have
+    /// already ended are dropped when a new one is scheduled.
+    pub fn schedule_pause(env: Env, caller: Address, start_ts: u64, end_ts: u64) -> bool {
+        caller.require_auth();
+        Self::require_role_at_least(&env, &caller, FamilyRole::Owner);
+        Self::require_not_paused(&env);
+
+        let now = env.ledger().timestamp();
+        if start_ts >= end_ts || end_ts <= now {
+            panic!("Invalid pause window");
+        }
+
+        let windows: Vec<PauseWindow> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("PAUSE_WIN"))
+            .unwrap_or_else(|| Vec::new(&env));
+        let mut active = Vec::new(&env);
+        for window in windows.iter() {
+            if window.end > now {
+                active.push_back(window);
+            }
+        }
+        if active.len() >= MAX_PAUSE_WINDOWS {
+            panic!("Too many scheduled pause windows");
+        }
+        active.push_back(PauseWindow {
+            start: start_ts,
+            end: end_ts,
+        });
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("PAUSE_WIN"), &active);
+        env.events().publish(
+            (symbol_short!("wallet"), symbol_short!("sched_pse")),
+            (start_ts, end_ts),
+        );
+
+        true
+    }
+
+    pub fn clear_scheduled_pauses(env: Env, caller: Address) -> bool {
+        caller.require_auth();
+        Self::require_role_at_least(&env, &caller, FamilyRole::Owner);
+
+        Self::extend_instance_ttl(&env);
+        env.storage().instance().remove(&symbol_short!("PAUSE_WIN"));
+
+        true
+    }
+
+    pub fn get_scheduled_pauses(env: Env) -> Vec<PauseWindow> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("PAUSE_WIN"))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    pub fn set_pause_admin(env: Env, caller: Address, new_admin: Address) -> bool {
+        caller.require_auth();
+        Self::require_role_at_least(&env, &caller, FamilyRole::Owner);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("PAUSE_ADM"), &new_admin);
+        true
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        Self::get_global_paused(&env)
+    }
+
+    /// Interface version for client compatibility checks. Returns the
+    /// version recorded by `set_version`, or `CONTRACT_VERSION` if none.
+    pub fn get_version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("VERSION"))
+            .unwrap_or(CONTRACT_VERSION)
+    }
+
+    /// Replace the instance TTL policy: from the next mutating call on, TTL
+    /// is bumped to `bump_amount` ledgers whenever it has fallen to
+    /// `threshold` or below. Requires `threshold < bump_amount <=` the
+    /// network's maximum TTL.
+    pub fn set_ttl_config(
+        env: Env,
+        owner: Address,
+        threshold: u32,
+        bump_amount: u32,
+    ) -> Result<bool, Error> {
+        owner.require_auth();
+        if !Self::has_owner_rights(&env, &owner) {
+            return Err(Error::Unauthorized);
+        }
+        if threshold >= bump_amount || bump_amount > env.storage().max_ttl() {
+            return Err(Error::InvalidAmount);
+        }
+
+        env.storage().instance().set(
+            &symbol_short!("TTL_CFG"),
+            &TtlConfig {
+                threshold,
+                bump_amount,
+            },
+        );
+        Ok(true)
+    }
+
+    /// Keep-alive: extend the instance TTL under the current policy. Anyone
+    /// may call it; no auth is required and no wallet state changes, though
+    /// like every TTL extension it publishes `(family, TtlExtended)`.
+    pub fn bump(env: Env) -> bool {
+        Self::extend_instance_ttl(&env);
+        true
+    }
+
+    pub fn get_ttl_config(env: Env) -> TtlConfig {
+        Self::ttl_config(&env)
+    }
+
+    fn get_upgrade_admin(env: &Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("UPG_ADM"))
+    }
+
+    pub fn set_upgrade_admin(env: Env, caller: Address, new_admin: Address) -> bool {
+        caller.require_auth();
+        Self::require_role_at_least(&env, &caller, FamilyRole::Owner);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("UPG_ADM"), &new_admin);
+        true
+    }
+
+    pub fn set_version(env: Env, caller: Address, new_version: u32) -> bool {
+        caller.require_auth();
+        let admin = Self::get_upgrade_admin(&env).unwrap_or_else(|| {
+            env.storage()
+                .instance()
+                .get(&symbol_short!("OWNER"))
+                .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized))
+        });
+        if admin != caller {
+            panic!("Only upgrade admin can set version");
+        }
+        let prev = Self::get_version(env.clone());
+        env.storage()
+            .instance()
+            .set(&symbol_short!("VERSION"), &new_version);
+        env.events().publish(
+            (symbol_short!("wallet"), symbol_short!("upgraded")),
+            (prev, new_version),
+        );
+        true
+    }
+
+    /// Replace this contract's code with the already-uploaded wasm
+    /// `new_wasm_hash`, keeping all storage. Owner-only; allowed while paused
+    /// so fixes can ship during an incident.
+    pub fn upgrade(env: Env, owner: Address, new_wasm_hash: BytesN<32>) -> bool {
+        owner.require_auth();
+        if !Self::has_owner_rights(&env, &owner) {
+            panic!("Only Owner can upgrade");
+        }
+
+        Self::extend_instance_ttl(&env);
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        env.events().publish(
+            (symbol_short!("wallet"), symbol_short!("wasm_upd")),
+            (owner, new_wasm_hash),
+        );
+        true
+    }
+
+    pub fn batch_add_family_members(
+        env: Env,
+        caller: Address,
+        members: Vec<BatchMemberItem>,
+    ) -> u32 {
+        caller.require_auth();
+        Self::require_role_at_least(&env, &caller, FamilyRole::Admin);
+        Self::require_not_paused(&env);
+        if Self::is_renounced(&env) {
+            panic!("Ownership has been renounced");
+        }
+        if members.len() > MAX_BATCH_MEMBERS {
+            panic!("Batch too large");
+        }
+        Self::extend_instance_ttl(&env);
+        let mut members_map: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        let timestamp = env.ledger().timestamp();
+        let mut count = 0u32;
+        for item in members.iter() {
+            if item.role == FamilyRole::Owner {
+                panic!("Cannot add Owner via batch");
+            }
+            if Self::has_owner_rights(&env, &item.address) {
+                panic!("Owner cannot be a limited member");
+            }
+            let record =
+                Self::readd_member(&members_map, item.address.clone(), item.role, timestamp);
+            members_map.set(item.address.clone(), record);
+            Self::append_access_audit(
+                &env,
+                symbol_short!("add_mem"),
+                &caller,
+                Some(item.address.clone()),
+                true,
+            );
+            env.events().publish(
+                (
+                    symbol_short!("added"),
+                    symbol_short!("member"),
+                    item.address.clone(),
+                ),
+                MemberAddedEvent {
+                    member: item.address,
+                    role: item.role,
+                    spending_limit: 0,
+                    timestamp,
+                },
+            );
+            count += 1;
+        }
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members_map);
+        Self::update_storage_stats(&env);
+        count
+    }
+
+    /// Strict batch onboarding: every entry is validated like `add_member`
+    /// before anything is written, so one bad entry rejects the whole batch.
+    /// Returns the number of members added.
+    pub fn add_members_batch(
+        env: Env,
+        caller: Address,
+        members: Vec<MemberSpec>,
+    ) -> Result<u32, Error> {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::is_owner_or_admin(&env, &caller) || Self::is_renounced(&env) {
+            return Err(Error::Unauthorized);
+        }
+        if members.len() > MAX_BATCH_MEMBERS {
+            return Err(Error::BatchTooLarge);
+        }
+
+        let mut members_map: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+
+        Self::validate_member_specs(&env, &members_map, &members)?;
+
+        Self::extend_instance_ttl(&env);
+        Self::insert_member_specs(&env, &caller, &mut members_map, members.clone());
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members_map);
+        Self::update_storage_stats(&env);
+
+        Ok(members.len())
+    }
+
+    /// Every member record with all internal fields (spend counters,
+    /// windows, timestamps), for backups and `import_members`. Owner only.
+    pub fn export_members(env: Env, owner: Address) -> Result<Vec<FamilyMember>, Error> {
+        owner.require_auth();
+        if !Self::has_owner_rights(&env, &owner) {
+            return Err(Error::Unauthorized);
+        }
+        let members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        Ok(members.values())
+    }
+
+    /// Restore records from `export_members`, field for field. Owner only.
+    /// `Owner`-role records and the current owners are skipped, since
+    /// ownership is restored through `init` and `add_admin`. Any record
+    /// already present (`InvalidRole`) or with a negative limit rejects the
+    /// whole import. Returns how many members were restored.
+    pub fn import_members(
+        env: Env,
+        owner: Address,
+        members: Vec<FamilyMember>,
+    ) -> Result<u32, Error> {
+        owner.require_auth();
+        if !Self::has_owner_rights(&env, &owner) {
+            return Err(Error::Unauthorized);
+        }
+        Self::require_not_paused(&env);
+
+        let mut members_map: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+
+        let mut restored = Vec::new(&env);
+        for record in members.iter() {
+            if record.role == FamilyRole::Owner || Self::has_owner_rights(&env, &record.address) {
+                continue;
+            }
+            if members_map.contains_key(record.address.clone()) {
+                return Err(Error::InvalidRole);
+            }
+            if record.spending_limit < 0 {
+                return Err(Error::InvalidSpendingLimit);
+            }
+            members_map.set(record.address.clone(), record.clone());
+            restored.push_back(record);
+        }
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members_map);
+        let now = env.ledger().timestamp();
+        for record in restored.iter() {
+            env.events().publish(
+                (
+                    symbol_short!("added"),
+                    symbol_short!("member"),
+                    record.address.clone(),
+                ),
+                MemberAddedEvent {
+                    member: record.address,
+                    role: record.role,
+                    spending_limit: record.spending_limit,
+                    timestamp: now,
+                },
+            );
+        }
+        Self::append_access_audit(&env, symbol_short!("import"), &owner, None, true);
+        Self::update_storage_stats(&env);
+
+        Ok(restored.len())
+    }
+
+    pub fn batch_remove_family_members(env: Env, caller: Address, addresses: Vec<Address>) -> u32 {
+        caller.require_auth();
+        Self::require_role_at_least(&env, &caller, FamilyRole::Owner);
+        let owner: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("OWNER"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        if !Self::has_owner_rights(&env, &caller) {
+            panic!("Only Owner can remove members");
+        }
+        Self::require_not_paused(&env);
+        if addresses.len() > MAX_BATCH_MEMBERS {
+            panic!("Batch too large");
+        }
+        Self::extend_instance_ttl(&env);
+        let mut members_map: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        let co_owners = Self::co_owners(&env);
+        let mut count = 0u32;
+        for addr in addresses.iter() {
+            if addr.clone() == owner || co_owners.contains(&addr) {
+                panic!("Cannot remove owner");
+            }
+            if members_map.get(addr.clone()).is_some() {
+                members_map.remove(addr.clone());
+                Self::drop_delegates(&env, &addr);
+                Self::append_access_audit(
+                    &env,
+                    symbol_short!("rem_mem"),
+                    &caller,
+                    Some(addr.clone()),
+                    true,
+                );
+                count += 1;
+            }
+        }
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members_map);
+        Self::update_storage_stats(&env);
+        count
+    }
+
+    /// Remove every member record except the owner's and co-owners', for
+    /// rebuilding a household from scratch. Returns the number removed.
+    pub fn clear_all_members(env: Env, caller: Address) -> u32 {
+        caller.require_auth();
+        if !Self::has_owner_rights(&env, &caller) {
+            panic!("Only Owner can remove members");
+        }
+        Self::require_not_paused(&env);
+        Self::extend_instance_ttl(&env);
+
+        let members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        let mut kept: Map<Address, FamilyMember> = Map::new(&env);
+        for (address, member) in members.iter() {
+            if Self::has_owner_rights(&env, &address) {
+                kept.set(address, member);
+            } else {
+                Self::drop_delegates(&env, &address);
+            }
+        }
+        let removed = members.len() - kept.len();
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &kept);
+
+        Self::append_access_audit(&env, symbol_short!("clr_mem"), &caller, None, true);
+        Self::update_storage_stats(&env);
+        env.events().publish(
+            (symbol_short!("family"), FamilyWalletEvent::MembersCleared),
+            (removed, caller),
+        );
+        removed
+    }
+
+    pub fn get_access_audit(env: Env, limit: u32) -> Vec<AccessAuditEntry> {
+        let entries: Vec<AccessAuditEntry> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("ACC_AUDIT"))
+            .unwrap_or_else(|| Vec::new(&env));
+        let n = entries.len().min(limit);
+        let mut out = Vec::new(&env);
+        for i in (entries.len().saturating_sub(n))..entries.len() {
+            if let Some(e) = entries.get(i) {
+                out.push_back(e);
+            }
+        }
+        out
+    }
+
+    // -----------------------------------------------------------------------
+    // Internal helpers
+    // -----------------------------------------------------------------------
+
+    fn execute_emergency_transfer_now(
+        env: Env,
+        proposer: Address,
+        token: Address,
+        recipient: Address,
+        amount: i128,
+    ) -> u64 {
+        let config: EmergencyConfig = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("EM_CONF"))
+            .expect("Emergency config not set");
+
+        if amount > config.max_amount {
+            panic!("Emergency amount exceeds maximum allowed");
+        }
+
+        let now = env.ledger().timestamp();
+        let last_ts: u64 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("EM_LAST"))
+            .unwrap_or(0u64);
+        if last_ts != 0 && now < last_ts.saturating_add(config.cooldown) {
+            panic!("Emergency transfer cooldown period not elapsed");
+        }
+
+        let token_client = TokenClient::new(&env, &token);
+        let current_balance = token_client.balance(&proposer);
+        if current_balance - amount < config.min_balance {
+            panic!("Emergency transfer would violate minimum balance requirement");
+        }
+
+        env.events().publish(
+            (symbol_short!("emerg"), EmergencyEvent::TransferInit),
+            (proposer.clone(), recipient.clone(), amount),
+        );
+
+        proposer.require_auth();
+        let _ = Self::execute_transaction_internal(
+            &env,
+            &proposer,
+            &TransactionType::EmergencyTransfer,
+            &TransactionData::EmergencyTransfer(token.clone(), recipient.clone(), amount),
+            false,
+        );
+
+        let store_ts: u64 = if now == 0 { 1u64 } else { now };
+        env.storage()
+            .instance()
+            .set(&symbol_short!("EM_LAST"), &store_ts);
+
+        env.events().publish(
+            (symbol_short!("emerg"), EmergencyEvent::TransferExec),
+            (proposer, recipient, amount),
+        );
+
+        0
+    }
+
+    fn execute_transaction_internal(
+        env: &Env,
+        proposer: &Address,
+        tx_type: &TransactionType,
+        data: &TransactionData,
         require_auth: bool,
     ) -> u64 {
         match (tx_type, data) {
@@ -1396,6 +5604,9 @@ impl FamilyWallet {
                 if require_auth {
                     proposer.require_auth();
                 }
+                if let Err(err) = Self::track_recipient(env, proposer, recipient) {
+                    panic_with_error!(env, err);
+                }
                 let token_client = TokenClient::new(env, token);
                 token_client.transfer(proposer, recipient, amount);
                 0
@@ -1406,38 +5617,775 @@ impl FamilyWallet {
                     .storage()
                     .instance()
                     .get(&symbol_short!("MEMBERS"))
-                    .expect("Wallet not initialized");
+                    .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+
+                if let Some(mut member_data) = members.get(member.clone()) {
+                    member_data.role = *new_role;
+                    members.set(member.clone(), member_data);
+                    env.storage()
+                        .instance()
+                        .set(&symbol_short!("MEMBERS"), &members);
+                    Self::append_access_audit(
+                        env,
+                        symbol_short!("role_chg"),
+                        proposer,
+                        Some(member.clone()),
+                        true,
+                    );
+                }
+                0
+            }
+            (
+                TransactionType::EmergencyTransfer,
+                TransactionData::EmergencyTransfer(token, recipient, amount),
+            ) => {
+                if require_auth {
+                    proposer.require_auth();
+                }
+                let token_client = TokenClient::new(env, token);
+                token_client.transfer(proposer, recipient, amount);
+                0
+            }
+            (TransactionType::PolicyCancellation, TransactionData::PolicyCancellation(..)) => 0,
+            _ => panic!("Invalid transaction type or data mismatch"),
+        }
+    }
+
+    /// Owner record, initial members and default configuration shared by
+    /// `init` and `initialize_with_members`. Callers handle auth and the
+    /// double-init check.
+    fn write_initial_state(env: &Env, owner: &Address, initial_members: Vec<Address>) {
+        Self::extend_instance_ttl(env);
+
+        let generation = Self::get_init_generation(env.clone()) + 1;
+        env.storage()
+            .persistent()
+            .set(&symbol_short!("INIT_GEN"), &generation);
+        env.storage().persistent().extend_ttl(
+            &symbol_short!("INIT_GEN"),
+            ARCHIVE_LIFETIME_THRESHOLD,
+            ARCHIVE_BUMP_AMOUNT,
+        );
+
+        env.storage().instance().set(&symbol_short!("OWNER"), owner);
+
+        let mut members: Map<Address, FamilyMember> = Map::new(env);
+        let timestamp = env.ledger().timestamp();
+
+        members.set(
+            owner.clone(),
+            Self::new_member(owner.clone(), FamilyRole::Owner, 0, timestamp),
+        );
+
+        for member_addr in initial_members.iter() {
+            members.set(
+                member_addr.clone(),
+                Self::new_member(member_addr.clone(), FamilyRole::Member, 0, timestamp),
+            );
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members);
+
+        let default_config = MultiSigConfig {
+            threshold: 2,
+            signers: Vec::new(env),
+            spending_limit: 1000_0000000,
+        };
+
+        for tx_type in [
+            TransactionType::LargeWithdrawal,
+            TransactionType::SplitConfigChange,
+            TransactionType::RoleChange,
+            TransactionType::EmergencyTransfer,
+            TransactionType::PolicyCancellation,
+        ] {
+            env.storage()
+                .instance()
+                .set(&Self::get_config_key(tx_type), &default_config.clone());
+        }
+
+        env.storage().instance().set(
+            &symbol_short!("PEND_TXS"),
+            &Map::<u64, PendingTransaction>::new(env),
+        );
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("EXEC_TXS"), &Map::<u64, bool>::new(env));
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("NEXT_TX"), &1u64);
+
+        let em_config = EmergencyConfig {
+            max_amount: 1000_0000000,
+            cooldown: 3600,
+            min_balance: 0,
+        };
+        env.storage()
+            .instance()
+            .set(&symbol_short!("EM_CONF"), &em_config);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("EM_MODE"), &false);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("EM_LAST"), &0u64);
+
+        env.events().publish(
+            (symbol_short!("family"), FamilyWalletEvent::Initialized),
+            owner.clone(),
+        );
+    }
 
-                if let Some(mut member_data) = members.get(member.clone()) {
-                    member_data.role = *new_role;
-                    members.set(member.clone(), member_data);
-                    env.storage()
-                        .instance()
-                        .set(&symbol_short!("MEMBERS"), &members);
-                    Self::append_access_audit(
-                        env,
-                        symbol_short!("role_chg"),
-                        proposer,
-                        Some(member.clone()),
-                        true,
-                    );
+    /// Validation shared by the strict onboarding entry points: no `Owner`
+    /// roles, no negative limits, and no address already in `members_map`
+    /// or repeated within `specs`.
+    fn validate_member_specs(
+        env: &Env,
+        members_map: &Map<Address, FamilyMember>,
+        specs: &Vec<MemberSpec>,
+    ) -> Result<(), Error> {
+        for (i, spec) in specs.iter().enumerate() {
+            if spec.role == FamilyRole::Owner {
+                return Err(Error::InvalidRole);
+            }
+            if spec.spending_limit < 0 {
+                return Err(Error::InvalidSpendingLimit);
+            }
+            Self::check_limit_bounds(env, spec.role, spec.spending_limit)?;
+            if members_map.contains_key(spec.address.clone()) {
+                return Err(Error::InvalidRole);
+            }
+            for earlier in specs.iter().take(i) {
+                if earlier.address == spec.address {
+                    return Err(Error::InvalidRole);
                 }
-                0
             }
+        }
+        Ok(())
+    }
+
+    /// Add already-validated `specs` to `members_map`, auditing and emitting
+    /// `MemberAddedEvent` for each. The caller persists the map.
+    fn insert_member_specs(
+        env: &Env,
+        caller: &Address,
+        members_map: &mut Map<Address, FamilyMember>,
+        specs: Vec<MemberSpec>,
+    ) {
+        let now = env.ledger().timestamp();
+        for spec in specs.iter() {
+            members_map.set(
+                spec.address.clone(),
+                Self::new_member(spec.address.clone(), spec.role, spec.spending_limit, now),
+            );
+            Self::append_access_audit(
+                env,
+                symbol_short!("add_mem"),
+                caller,
+                Some(spec.address.clone()),
+                true,
+            );
+            env.events().publish(
+                (
+                    symbol_short!("added"),
+                    symbol_short!("member"),
+                    spec.address.clone(),
+                ),
+                MemberAddedEvent {
+                    member: spec.address,
+                    role: spec.role,
+                    spending_limit: spec.spending_limit,
+                    timestamp: now,
+                },
+            );
+        }
+    }
+
+    fn new_member(
+        address: Address,
+        role: FamilyRole,
+        spending_limit: i128,
+        added_at: u64,
+    ) -> FamilyMember {
+        FamilyMember {
+            address,
+            role,
+            spending_limit,
+            added_at,
+            frozen: false,
+            limit_period: LimitPeriod::Lifetime,
+            period_start: added_at,
+            spent_in_period: 0,
+            spent: 0,
+            active: true,
+            max_recipients: 0,
+            balance: 0,
+            approver: None,
+            auto_topup_on_deposit: false,
+            limit_expires_at: None,
+            tx_count: 0,
+            max_tx_count: 0,
+            birth_timestamp: None,
+            limit_per_year_of_age: 0,
+            name: None,
+        }
+    }
+
+    /// A fresh record for the overwriting legacy add paths, keeping the
+    /// original `added_at` and `name` when `address` is already a member.
+    fn readd_member(
+        members: &Map<Address, FamilyMember>,
+        address: Address,
+        role: FamilyRole,
+        now: u64,
+    ) -> FamilyMember {
+        let mut record = Self::new_member(address.clone(), role, 0, now);
+        if let Some(existing) = members.get(address) {
+            record.added_at = existing.added_at;
+            record.name = existing.name;
+        }
+        record
+    }
+
+    fn period_seconds(period: LimitPeriod) -> Option<u64> {
+        match period {
+            LimitPeriod::Daily => Some(DAY_SECONDS),
+            LimitPeriod::Weekly => Some(WEEK_SECONDS),
+            LimitPeriod::Monthly => Some(MONTH_SECONDS),
+            LimitPeriod::Lifetime => None,
+        }
+    }
+
+    /// Advance the member's spending window to the one containing `now`,
+    /// clearing the accumulated spend if a boundary was crossed.
+    fn roll_spend_window(member: &mut FamilyMember, now: u64) {
+        let length = match Self::period_seconds(member.limit_period) {
+            Some(length) => length,
+            None => return,
+        };
+        if now < member.period_start.saturating_add(length) {
+            return;
+        }
+        let elapsed_periods = (now - member.period_start) / length;
+        member.period_start += elapsed_periods * length;
+        member.spent_in_period = 0;
+        member.tx_count = 0;
+    }
+
+    /// Body of `spend` after auth.
+    fn settle_spend(env: &Env, member_address: &Address, amount: i128) -> bool {
+        Self::require_not_paused(env);
+        Self::require_no_scheduled_pause(env);
+
+        if amount <= 0 {
+            Self::alert_owner_on_decline(env, member_address, amount, Error::InvalidAmount);
+            return false;
+        }
+
+        let mut members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+
+        let mut record = match members.get(member_address.clone()) {
+            Some(m) => m,
+            None => {
+                Self::alert_owner_on_decline(env, member_address, amount, Error::MemberNotFound);
+                return false;
+            }
+        };
+        if let Err(reason) = Self::apply_spend(env, &mut record, amount)
+            .and_then(|_| Self::apply_wallet_cap(env, amount))
+        {
+            Self::alert_owner_on_decline(env, member_address, amount, reason);
+            return false;
+        }
+        Self::apply_wallet_rate_limit(env);
+        Self::add_to_spend_bucket(env, &record, amount);
+        members.set(member_address.clone(), record);
+
+        Self::extend_instance_ttl(env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members);
+        Self::append_spend_history(env, member_address, amount);
+
+        true
+    }
+
+    /// Body of `spend_token` after auth.
+    fn settle_token_spend(
+        env: &Env,
+        member_address: &Address,
+        token: &Address,
+        amount: i128,
+        recipient: &Address,
+    ) -> Result<bool, Error> {
+        Self::require_not_paused(env);
+        Self::require_no_scheduled_pause(env);
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let mut members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+        let mut record = members
+            .get(member_address.clone())
+            .ok_or(Error::MemberNotFound)?;
+        Self::apply_spend(env, &mut record, amount)?;
+        if !Self::token_cap_allows(env, &record, token, amount) {
+            return Err(Error::SpendingLimitExceeded);
+        }
+
+        let token_client = TokenClient::new(env, token);
+        let contract = env.current_contract_address();
+        if token_client.balance(&contract) < amount {
+            return Err(Error::InsufficientBalance);
+        }
+        // A held payment returns `Ok`, so it must not reach the wallet-wide
+        // counters below: their writes would stick.
+        if Self::needs_recipient_approval(env, &record, recipient) {
+            Self::hold_for_recipient_approval(env, member_address, recipient);
+            return Ok(false);
+        }
+        Self::apply_wallet_cap(env, amount)?;
+        Self::apply_wallet_rate_limit(env);
+        Self::track_recipient(env, member_address, recipient)?;
+        Self::remember_recipient(env, member_address, recipient);
+
+        Self::add_to_spend_bucket(env, &record, amount);
+        members.set(member_address.clone(), record);
+
+        Self::extend_instance_ttl(env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members);
+        Self::append_spend_history(env, member_address, amount);
+
+        token_client.transfer(&contract, recipient, &amount);
+
+        Ok(true)
+    }
+
+    /// Body of `execute_withdrawal` after auth.
+    fn settle_withdrawal(env: &Env, member_address: &Address, id: u64) -> Result<bool, Error> {
+        let mut requests: Map<u64, WithdrawalRequest> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("WD_REQS"))
+            .unwrap_or_else(|| Map::new(env));
+        let request = requests.get(id).ok_or(Error::TransactionNotFound)?;
+        if request.member != *member_address {
+            return Err(Error::Unauthorized);
+        }
+        if env.ledger().timestamp() < request.executable_at {
+            return Err(Error::WithdrawalDelayActive);
+        }
+
+        Self::commit_spend(env, member_address, request.amount)?;
+        requests.remove(id);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("WD_REQS"), &requests);
+
+        env.events().publish(
             (
-                TransactionType::EmergencyTransfer,
-                TransactionData::EmergencyTransfer(token, recipient, amount),
-            ) => {
-                if require_auth {
-                    proposer.require_auth();
-                }
-                let token_client = TokenClient::new(env, token);
-                token_client.transfer(proposer, recipient, amount);
-                0
+                symbol_short!("withdraw"),
+                symbol_short!("executed"),
+                member_address.clone(),
+            ),
+            (id, member_address.clone(), request.amount),
+        );
+
+        Ok(true)
+    }
+
+    /// Forget `member`'s delegate signers, so a later re-add starts clean.
+    fn drop_delegates(env: &Env, member: &Address) {
+        let mut delegates: Map<Address, Vec<Address>> =
+            match env.storage().instance().get(&symbol_short!("DELEGATES")) {
+                Some(delegates) => delegates,
+                None => return,
+            };
+        if delegates.contains_key(member.clone()) {
+            delegates.remove(member.clone());
+            env.storage()
+                .instance()
+                .set(&symbol_short!("DELEGATES"), &delegates);
+        }
+    }
+
+    /// Require `delegate`'s auth and that it is registered for `member`.
+    fn require_delegate(env: &Env, delegate: &Address, member: &Address) -> Result<(), Error> {
+        delegate.require_auth();
+        if !Self::get_delegates(env.clone(), member.clone()).contains(delegate) {
+            return Err(Error::Unauthorized);
+        }
+        Ok(())
+    }
+
+    /// Body of `record_spend` after auth: check `amount` against the
+    /// member's and the wallet's limits, then persist it.
+    fn commit_spend(env: &Env, member_address: &Address, amount: i128) -> Result<(), Error> {
+        Self::require_not_paused(env);
+        Self::require_no_scheduled_pause(env);
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let mut members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+
+        let mut record = members
+            .get(member_address.clone())
+            .ok_or(Error::MemberNotFound)?;
+        Self::apply_spend(env, &mut record, amount)?;
+        Self::apply_wallet_cap(env, amount)?;
+        Self::apply_wallet_rate_limit(env);
+        Self::add_to_spend_bucket(env, &record, amount);
+        members.set(member_address.clone(), record);
+
+        Self::extend_instance_ttl(env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members);
+        Self::append_spend_history(env, member_address, amount);
+
+        Ok(())
+    }
+
+    /// Validate and accumulate a spend on an in-memory member record. The
+    /// caller is responsible for persisting the record.
+    fn apply_spend(env: &Env, member: &mut FamilyMember, amount: i128) -> Result<(), Error> {
+        if member.frozen {
+            return Err(Error::MemberFrozen);
+        }
+        if !member.active {
+            return Err(Error::MemberInactive);
+        }
+        if Self::limit_expired(env, member) {
+            return Err(Error::LimitExpired);
+        }
+
+        Self::roll_spend_window(member, env.ledger().timestamp());
+
+        let new_total = Self::add_or_panic(member.spent_in_period, amount);
+        if !Self::is_unlimited(member) && new_total > Self::effective_limit(env, member) {
+            return Err(Error::SpendingLimitExceeded);
+        }
+        if member.max_tx_count > 0
+            && !matches!(member.role, FamilyRole::Owner | FamilyRole::Admin)
+            && member.tx_count >= member.max_tx_count
+        {
+            return Err(Error::TxCountExceeded);
+        }
+
+        member.spent_in_period = new_total;
+        member.spent = Self::add_or_panic(member.spent, amount);
+        member.tx_count = member.tx_count.saturating_add(1);
+        Ok(())
+    }
+
+    /// Move the wallet cap into the month containing `now`, carrying unused
+    /// budget forward when rollover is enabled.
+    fn roll_wallet_cap(cap: &mut WalletMonthlyCap, now: u64) {
+        if now < cap.period_start.saturating_add(MONTH_SECONDS) {
+            return;
+        }
+        let elapsed_months = (now - cap.period_start) / MONTH_SECONDS;
+        let unused = (cap.limit.saturating_add(cap.carried) - cap.spent).max(0);
+        let mut carried = unused.min(cap.max_rollover);
+        if elapsed_months > 1 {
+            // Months with no spending carry their full budget.
+            carried = cap.limit.saturating_add(carried).min(cap.max_rollover);
+        }
+        cap.period_start += elapsed_months * MONTH_SECONDS;
+        cap.spent = 0;
+        cap.carried = carried;
+    }
+
+    /// Charge a spend against the wallet monthly cap, if one is set.
+    fn apply_wallet_cap(env: &Env, amount: i128) -> Result<(), Error> {
+        let mut cap: WalletMonthlyCap =
+            match env.storage().instance().get(&symbol_short!("WAL_CAP")) {
+                Some(cap) => cap,
+                None => return Ok(()),
+            };
+        Self::roll_wallet_cap(&mut cap, env.ledger().timestamp());
+        let new_spent = Self::add_or_panic(cap.spent, amount);
+        if new_spent > Self::add_or_panic(cap.limit, cap.carried) {
+            return Err(Error::WalletCapExceeded);
+        }
+        cap.spent = new_spent;
+        env.storage()
+            .instance()
+            .set(&symbol_short!("WAL_CAP"), &cap);
+        Ok(())
+    }
+
+    /// Read-side totals saturate rather than panic so dashboards keep
+    /// working with extreme values.
+    fn saturating_total(
+        members: &Map<Address, FamilyMember>,
+        value: fn(&FamilyMember) -> i128,
+    ) -> i128 {
+        members
+            .iter()
+            .fold(0, |total, (_, member)| total.saturating_add(value(&member)))
+    }
+
+    /// `a + b` for running totals (spends, balances, budgets); panics with
+    /// `"Arithmetic overflow"` instead of wrapping.
+    fn add_or_panic(a: i128, b: i128) -> i128 {
+        a.checked_add(b)
+            .unwrap_or_else(|| panic!("Arithmetic overflow"))
+    }
+
+    fn roll_wallet_rate_limit(limit: &mut WalletRateLimit, now: u64) {
+        if now < limit.window_start.saturating_add(limit.window_secs) {
+            return;
+        }
+        let elapsed_windows = (now - limit.window_start) / limit.window_secs;
+        limit.window_start += elapsed_windows * limit.window_secs;
+        limit.count = 0;
+    }
+
+    /// Count a spend against the wallet rate limit, if one is set.
+    fn apply_wallet_rate_limit(env: &Env) {
+        let mut limit: WalletRateLimit =
+            match env.storage().instance().get(&symbol_short!("WAL_RATE")) {
+                Some(limit) => limit,
+                None => return,
+            };
+        Self::roll_wallet_rate_limit(&mut limit, env.ledger().timestamp());
+        if limit.count >= limit.max_spends {
+            panic!("Wallet rate limit exceeded");
+        }
+        limit.count += 1;
+        env.storage()
+            .instance()
+            .set(&symbol_short!("WAL_RATE"), &limit);
+    }
+
+    /// Quorum for a transaction: the highest matching approval tier for
+    /// withdrawals, otherwise the configured multisig threshold.
+    fn required_approvals(env: &Env, config: &MultiSigConfig, data: &TransactionData) -> u32 {
+        let amount = match data {
+            TransactionData::Withdrawal(_, _, amount) => *amount,
+            _ => return config.threshold,
+        };
+
+        let tiers: Vec<ApprovalTier> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("APPR_TIER"))
+            .unwrap_or_else(|| Vec::new(env));
+
+        let mut required = config.threshold;
+        for tier in tiers.iter() {
+            if amount >= tier.min_amount {
+                required = tier.required_approvals;
             }
-            (TransactionType::PolicyCancellation, TransactionData::PolicyCancellation(..)) => 0,
-            _ => panic!("Invalid transaction type or data mismatch"),
         }
+        required
+    }
+
+    /// Append a spend record for a member, pruning records that have fallen
+    /// out of the retention window.
+    fn append_spend_history(env: &Env, member_address: &Address, amount: i128) {
+        let mut histories: Map<Address, Vec<SpendRecord>> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("SPND_HIST"))
+            .unwrap_or_else(|| Map::new(env));
+        let records = histories
+            .get(member_address.clone())
+            .unwrap_or_else(|| Vec::new(env));
+
+        let mut kept = Self::retained_spend_records(env, &records);
+        kept.push_back(SpendRecord {
+            amount,
+            timestamp: env.ledger().timestamp(),
+        });
+        histories.set(member_address.clone(), kept);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("SPND_HIST"), &histories);
+    }
+
+    fn member_approver(env: &Env, member_address: &Address) -> Option<Address> {
+        env.storage()
+            .instance()
+            .get::<_, Map<Address, FamilyMember>>(&symbol_short!("MEMBERS"))
+            .and_then(|members| members.get(member_address.clone()))
+            .and_then(|member| member.approver)
+    }
+
+    /// `(start, length)` of the trend bucket containing `now`: the member's
+    /// spending window, or a day aligned to `period_start` for `Lifetime`.
+    fn spend_bucket(member: &FamilyMember, now: u64) -> (u64, u64) {
+        let length = Self::period_seconds(member.limit_period).unwrap_or(DAY_SECONDS);
+        let elapsed = now.saturating_sub(member.period_start);
+        (member.period_start + elapsed / length * length, length)
+    }
+
+    /// Add a spend to the member's current trend bucket, dropping buckets
+    /// older than `MAX_TREND_PERIODS` windows.
+    fn add_to_spend_bucket(env: &Env, member: &FamilyMember, amount: i128) {
+        let (current, length) = Self::spend_bucket(member, env.ledger().timestamp());
+        let oldest = current.saturating_sub((MAX_TREND_PERIODS as u64 - 1) * length);
+
+        let mut all: Map<Address, Map<u64, i128>> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("SPND_BKT"))
+            .unwrap_or_else(|| Map::new(env));
+        let buckets = all
+            .get(member.address.clone())
+            .unwrap_or_else(|| Map::new(env));
+
+        let mut kept = Map::new(env);
+        for (start, total) in buckets.iter() {
+            if start >= oldest {
+                kept.set(start, total);
+            }
+        }
+        kept.set(
+            current,
+            Self::add_or_panic(kept.get(current).unwrap_or(0), amount),
+        );
+        all.set(member.address.clone(), kept);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("SPND_BKT"), &all);
+    }
+
+    /// Append to the member's limit history, keeping the newest
+    /// `MAX_LIMIT_HISTORY` entries.
+    fn append_limit_history(env: &Env, member_address: &Address, change: LimitChange) {
+        let mut histories: Map<Address, Vec<LimitChange>> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("LIM_HIST"))
+            .unwrap_or_else(|| Map::new(env));
+        let mut history = histories
+            .get(member_address.clone())
+            .unwrap_or_else(|| Vec::new(env));
+        history.push_back(change);
+        while history.len() > MAX_LIMIT_HISTORY {
+            history.pop_front();
+        }
+        histories.set(member_address.clone(), history);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("LIM_HIST"), &histories);
+    }
+
+    fn retained_spend_records(env: &Env, records: &Vec<SpendRecord>) -> Vec<SpendRecord> {
+        let retention: u64 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("HIST_RET"))
+            .unwrap_or(0);
+        if retention == 0 {
+            return records.clone();
+        }
+
+        let now = env.ledger().timestamp();
+        let mut kept = Vec::new(env);
+        for record in records.iter() {
+            if now.saturating_sub(record.timestamp) <= retention {
+                kept.push_back(record);
+            }
+        }
+        kept
+    }
+
+    /// Enforce `LIM_BND` on a Member/Viewer limit. Owner/Admin limits are
+    /// never applied, so they are not checked.
+    /// Whether `member`'s `set_token_limit` cap for `token` covers `amount`.
+    /// Owners and Admins are uncapped; otherwise an unlisted token is
+    /// rejected and `0` is unlimited.
+    fn token_cap_allows(env: &Env, member: &FamilyMember, token: &Address, amount: i128) -> bool {
+        if member.role == FamilyRole::Owner || member.role == FamilyRole::Admin {
+            return true;
+        }
+        match Self::get_token_limits(env.clone(), member.address.clone()).get(token.clone()) {
+            None => false,
+            Some(0) => true,
+            Some(limit) => amount <= limit,
+        }
+    }
+
+    fn check_limit_bounds(env: &Env, role: FamilyRole, limit: i128) -> Result<(), Error> {
+        if matches!(role, FamilyRole::Owner | FamilyRole::Admin) {
+            return Ok(());
+        }
+        let bounds = match Self::get_limit_bounds(env.clone()) {
+            Some(bounds) => bounds,
+            None => return Ok(()),
+        };
+        let below_min = bounds.min_limit > 0 && limit != 0 && limit < bounds.min_limit;
+        let above_max = bounds.max_limit > 0 && (limit == 0 || limit > bounds.max_limit);
+        if below_min || above_max {
+            return Err(Error::LimitOutOfBounds);
+        }
+        Ok(())
+    }
+
+    /// `spent * 100 / limit` for a positive `limit`. Spends too large to
+    /// multiply by 100 fall back to whole multiples of the limit.
+    fn utilization_percent(spent: i128, limit: i128) -> i128 {
+        match spent.checked_mul(100) {
+            Some(scaled) => scaled / limit,
+            None => (spent / limit).saturating_mul(100),
+        }
+    }
+
+    fn limit_expired(env: &Env, member: &FamilyMember) -> bool {
+        matches!(member.limit_expires_at, Some(expires_at) if env.ledger().timestamp() > expires_at)
+    }
+
+    fn is_unlimited(member: &FamilyMember) -> bool {
+        matches!(member.role, FamilyRole::Owner | FamilyRole::Admin) || member.spending_limit == 0
+    }
+
+    /// `spending_limit` grown by the member's age scaling, if any. Every
+    /// spend check uses this instead of the stored base.
+    fn effective_limit(env: &Env, member: &FamilyMember) -> i128 {
+        let birth = match member.birth_timestamp {
+            Some(birth) if member.spending_limit > 0 => birth,
+            _ => return member.spending_limit,
+        };
+        let years = env.ledger().timestamp().saturating_sub(birth) / YEAR_SECONDS;
+        let bonus = member
+            .limit_per_year_of_age
+            .saturating_mul(i128::from(years));
+        member.spending_limit.saturating_add(bonus)
+    }
+
+    /// Remaining allowance for the window containing the current ledger
+    /// timestamp, never negative.
+    fn remaining_limit(env: &Env, member: &FamilyMember) -> i128 {
+        if Self::is_unlimited(member) {
+            return i128::MAX;
+        }
+        let mut current = member.clone();
+        Self::roll_spend_window(&mut current, env.ledger().timestamp());
+        (Self::effective_limit(env, &current) - current.spent_in_period).max(0)
     }
 
     fn get_config_key(tx_type: TransactionType) -> Symbol {
@@ -1461,6 +6409,25 @@ impl FamilyWallet {
         members.get(address.clone()).is_some()
     }
 
+    fn co_owners(env: &Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("ADMINS"))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    /// Whether `renounce_ownership` has run, leaving `OWNER` pointing at
+    /// the contract itself.
+    fn is_renounced(env: &Env) -> bool {
+        Self::require_initialized(env) == env.current_contract_address()
+    }
+
+    /// The root owner or any co-owner. Panics with `NotInitialized` before
+    /// `init`.
+    fn has_owner_rights(env: &Env, address: &Address) -> bool {
+        *address == Self::require_initialized(env) || Self::co_owners(env).contains(address)
+    }
+
     fn is_owner_or_admin(env: &Env, address: &Address) -> bool {
         let members: Map<Address, FamilyMember> = env
             .storage()
@@ -1482,6 +6449,174 @@ impl FamilyWallet {
         }
     }
 
+    /// Shared body of `increase_spending_limit`/`decrease_spending_limit`:
+    /// resolves the current limit, applies `delta` and hands the result to
+    /// `set_spending_limit` so audit, history and events stay identical.
+    fn adjust_spending_limit(
+        env: &Env,
+        caller: &Address,
+        member_address: Address,
+        delta: i128,
+        increase: bool,
+    ) -> Result<i128, Error> {
+        if Self::is_change_reason_required(env) {
+            return Err(Error::ReasonRequired);
+        }
+        if delta <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if !Self::is_owner_or_admin(env, caller) {
+            return Err(Error::Unauthorized);
+        }
+
+        let members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized));
+        let current = members
+            .get(member_address.clone())
+            .ok_or(Error::MemberNotFound)?
+            .spending_limit;
+        if current == 0 {
+            return Err(Error::InvalidSpendingLimit);
+        }
+
+        let new_limit = if increase {
+            current.checked_add(delta)
+        } else {
+            current.checked_sub(delta)
+        }
+        .filter(|limit| *limit > 0)
+        .ok_or(Error::InvalidSpendingLimit)?;
+
+        Self::set_spending_limit(env, caller, member_address, new_limit, None)?;
+        Ok(new_limit)
+    }
+
+    /// Shared body of the limit-update entry points. Returns the previous
+    /// limit.
+    fn set_spending_limit(
+        env: &Env,
+        caller: &Address,
+        member_address: Address,
+        new_limit: i128,
+        reason: Option<String>,
+    ) -> Result<i128, Error> {
+        Self::require_not_paused(env);
+
+        if !Self::is_owner_or_admin(env, caller) {
+            return Err(Error::Unauthorized);
+        }
+        Self::require_no_dispute(env)?;
+        if new_limit < 0 {
+            return Err(Error::InvalidSpendingLimit);
+        }
+
+        let mut members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+
+        let mut record = members
+            .get(member_address.clone())
+            .ok_or(Error::MemberNotFound)?;
+        if !Self::can_manage_member(&members, caller, &record) {
+            return Err(Error::Unauthorized);
+        }
+        Self::check_limit_bounds(env, record.role, new_limit)?;
+        if reason
+            .as_ref()
+            .is_some_and(|memo| memo.len() > MAX_MEMO_LEN)
+        {
+            return Err(Error::MemoTooLong);
+        }
+
+        let old_limit = record.spending_limit;
+        if Self::get_block_increase_when_over(env.clone())
+            && old_limit > 0
+            && (new_limit == 0 || new_limit > old_limit)
+        {
+            let mut window = record.clone();
+            Self::roll_spend_window(&mut window, env.ledger().timestamp());
+            if window.spent_in_period > old_limit {
+                return Err(Error::MemberOverLimit);
+            }
+        }
+        record.spending_limit = new_limit;
+        members.set(member_address.clone(), record);
+
+        Self::extend_instance_ttl(env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members);
+
+        Self::record_access_audit(
+            env,
+            symbol_short!("upd_lim"),
+            caller,
+            Some(member_address.clone()),
+            true,
+            reason.clone(),
+        );
+
+        let now = env.ledger().timestamp();
+        Self::append_limit_history(
+            env,
+            &member_address,
+            LimitChange {
+                old_limit,
+                new_limit,
+                timestamp: now,
+                memo: reason.clone(),
+            },
+        );
+        env.events().publish(
+            (
+                symbol_short!("updated"),
+                symbol_short!("limit"),
+                member_address.clone(),
+            ),
+            SpendingLimitUpdatedEvent {
+                member: member_address,
+                old_limit,
+                new_limit,
+                timestamp: now,
+                memo: reason,
+            },
+        );
+
+        Ok(old_limit)
+    }
+
+    fn is_change_reason_required(env: &Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("REQ_RSN"))
+            .unwrap_or(false)
+    }
+
+    /// The Owner may manage any member's spending settings; an Admin only
+    /// those of strictly lower-privileged members.
+    fn can_manage_member(
+        members: &Map<Address, FamilyMember>,
+        caller: &Address,
+        target: &FamilyMember,
+    ) -> bool {
+        let caller_role = match members.get(caller.clone()) {
+            Some(member) => member.role,
+            None => return false,
+        };
+        match caller_role {
+            FamilyRole::Owner => true,
+            FamilyRole::Admin => {
+                Self::role_ordinal(target.role) > Self::role_ordinal(FamilyRole::Admin)
+            }
+            _ => false,
+        }
+    }
+
     fn role_ordinal(role: FamilyRole) -> u32 {
         role as u32
     }
@@ -1507,7 +6642,7 @@ impl FamilyWallet {
             .storage()
             .instance()
             .get(&symbol_short!("MEMBERS"))
-            .expect("Wallet not initialized");
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
         let member = members.get(caller.clone()).expect("Not a family member");
         if Self::role_has_expired(env, caller) {
             panic!("Role has expired");
@@ -1517,12 +6652,33 @@ impl FamilyWallet {
         }
     }
 
+    /// Insert after every entry with a timestamp `<=` the new one, keeping
+    /// `trail` chronological and stable for equal timestamps.
+    fn insert_audit_entry(trail: &mut Vec<MemberAuditEntry>, entry: MemberAuditEntry) {
+        let mut index = trail.len();
+        while index > 0 && trail.get(index - 1).unwrap().timestamp > entry.timestamp {
+            index -= 1;
+        }
+        trail.insert(index, entry);
+    }
+
     fn append_access_audit(
         env: &Env,
         operation: Symbol,
         caller: &Address,
         target: Option<Address>,
         success: bool,
+    ) {
+        Self::record_access_audit(env, operation, caller, target, success, None);
+    }
+
+    fn record_access_audit(
+        env: &Env,
+        operation: Symbol,
+        caller: &Address,
+        target: Option<Address>,
+        success: bool,
+        reason: Option<String>,
     ) {
         let mut entries: Vec<AccessAuditEntry> = env
             .storage()
@@ -1535,6 +6691,7 @@ impl FamilyWallet {
             target,
             timestamp: env.ledger().timestamp(),
             success,
+            reason,
         });
         let n = entries.len();
         if n > MAX_ACCESS_AUDIT_ENTRIES {
@@ -1561,16 +6718,216 @@ impl FamilyWallet {
             .unwrap_or(false)
     }
 
+    fn require_no_dispute(env: &Env) -> Result<(), Error> {
+        if Self::get_dispute_until(env.clone()).is_some() {
+            return Err(Error::DisputeActive);
+        }
+        Ok(())
+    }
+
     fn require_not_paused(env: &Env) {
         if Self::get_global_paused(env) {
             panic!("Contract is paused");
         }
     }
 
+    /// Record `recipient` in the member's current recipient window, failing
+    /// with `RecipientLimitReached` if it is new and the member's
+    /// `max_recipients` is already reached. Only capped members are tracked.
+    fn track_recipient(
+        env: &Env,
+        member_address: &Address,
+        recipient: &Address,
+    ) -> Result<(), Error> {
+        let members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| Map::new(env));
+        let mut member = match members.get(member_address.clone()) {
+            Some(member) if member.max_recipients > 0 => member,
+            _ => return Ok(()),
+        };
+        Self::roll_spend_window(&mut member, env.ledger().timestamp());
+
+        let mut windows: Map<Address, RecipientWindow> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("RCPT_WIN"))
+            .unwrap_or_else(|| Map::new(env));
+        let mut window = windows
+            .get(member_address.clone())
+            .filter(|window| window.period_start == member.period_start)
+            .unwrap_or_else(|| RecipientWindow {
+                period_start: member.period_start,
+                recipients: Vec::new(env),
+            });
+
+        if window.recipients.contains(recipient) {
+            return Ok(());
+        }
+        if window.recipients.len() >= member.max_recipients {
+            return Err(Error::RecipientLimitReached);
+        }
+
+        window.recipients.push_back(recipient.clone());
+        windows.set(member_address.clone(), window);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("RCPT_WIN"), &windows);
+        Ok(())
+    }
+
+    fn needs_recipient_approval(env: &Env, member: &FamilyMember, recipient: &Address) -> bool {
+        Self::get_recipient_approval(env.clone())
+            && !matches!(member.role, FamilyRole::Owner | FamilyRole::Admin)
+            && !Self::is_known_recipient(env.clone(), member.address.clone(), recipient.clone())
+    }
+
+    fn remember_recipient(env: &Env, member_address: &Address, recipient: &Address) {
+        let mut known: Map<Address, Vec<Address>> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("RCPT_KNWN"))
+            .unwrap_or_else(|| Map::new(env));
+        let mut recipients = known
+            .get(member_address.clone())
+            .unwrap_or_else(|| Vec::new(env));
+        if recipients.contains(recipient) {
+            return;
+        }
+        recipients.push_back(recipient.clone());
+        known.set(member_address.clone(), recipients);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("RCPT_KNWN"), &known);
+    }
+
+    /// Record a held first payment and tell the owner about it.
+    fn hold_for_recipient_approval(env: &Env, member_address: &Address, recipient: &Address) {
+        let mut pending: Map<Address, Vec<Address>> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("RCPT_PEND"))
+            .unwrap_or_else(|| Map::new(env));
+        let mut held = pending
+            .get(member_address.clone())
+            .unwrap_or_else(|| Vec::new(env));
+        if !held.contains(recipient) {
+            held.push_back(recipient.clone());
+            pending.set(member_address.clone(), held);
+            Self::extend_instance_ttl(env);
+            env.storage()
+                .instance()
+                .set(&symbol_short!("RCPT_PEND"), &pending);
+        }
+        env.events().publish(
+            (
+                symbol_short!("recip"),
+                symbol_short!("pending"),
+                member_address.clone(),
+            ),
+            recipient.clone(),
+        );
+    }
+
+    fn alert_owner_on_decline(env: &Env, member_address: &Address, amount: i128, reason: Error) {
+        let enabled: bool = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("DCL_ALRT"))
+            .unwrap_or(false);
+        if !enabled {
+            return;
+        }
+        let owner: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("OWNER"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        env.events().publish(
+            (symbol_short!("alert"), owner),
+            OwnerAlertEvent {
+                member: member_address.clone(),
+                amount,
+                reason: reason as u32,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+    }
+
+    fn allocation_summary(env: &Env, token: &Address) -> (i128, i128) {
+        let members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| Map::new(env));
+        let mut allocated = 0i128;
+        for (_, member) in members.iter() {
+            allocated = Self::add_or_panic(allocated, member.balance);
+        }
+        let held = TokenClient::new(env, token).balance(&env.current_contract_address());
+        (allocated, held - allocated)
+    }
+
+    /// The wallet owner, panicking with `"Wallet not initialized"` before
+    /// `init` has run.
+    fn require_initialized(env: &Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("OWNER"))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized))
+    }
+
+    fn in_scheduled_pause(env: &Env) -> bool {
+        let windows: Vec<PauseWindow> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("PAUSE_WIN"))
+            .unwrap_or_else(|| Vec::new(env));
+        let now = env.ledger().timestamp();
+        windows
+            .iter()
+            .any(|window| now >= window.start && now < window.end)
+    }
+
+    fn require_no_scheduled_pause(env: &Env) {
+        if Self::in_scheduled_pause(env) {
+            panic!("Spending paused for scheduled window");
+        }
+    }
+
     fn extend_instance_ttl(env: &Env) {
+        let config = Self::ttl_config(env);
+        env.storage()
+            .instance()
+            .extend_ttl(config.threshold, config.bump_amount);
+        env.events().publish(
+            (symbol_short!("family"), FamilyWalletEvent::TtlExtended),
+            TtlExtendedEvent {
+                bump_amount: config.bump_amount,
+                ledger: env.ledger().sequence(),
+            },
+        );
+    }
+
+    fn ttl_config(env: &Env) -> TtlConfig {
         env.storage()
             .instance()
-            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+            .get(&symbol_short!("TTL_CFG"))
+            .unwrap_or(TtlConfig {
+                threshold: INSTANCE_LIFETIME_THRESHOLD,
+                bump_amount: INSTANCE_BUMP_AMOUNT,
+            })
+    }
+
+    /// Initialization is remembered both by `OWNER` in instance storage
+    /// and by the `INIT_GEN` persistent entry, so losing the instance data
+    /// alone does not reopen `init`. An archived `INIT_GEN` has to be
+    /// restored before `init` can even run.
+    fn was_initialized(env: &Env) -> bool {
+        env.storage().instance().has(&symbol_short!("OWNER"))
+            || env.storage().persistent().has(&symbol_short!("INIT_GEN"))
     }
 
     fn extend_archive_ttl(env: &Env) {
@@ -1579,6 +6936,75 @@ impl FamilyWallet {
             .extend_ttl(ARCHIVE_LIFETIME_THRESHOLD, ARCHIVE_BUMP_AMOUNT);
     }
 
+    /// Count (and with `repair`, fix) the inconsistencies described on
+    /// `verify_integrity`.
+    fn reconcile_members(env: &Env, repair: bool) -> u32 {
+        let owner = Self::require_initialized(env);
+        let mut members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap_or_else(|| Map::new(env));
+        let mut issues = 0u32;
+
+        for (address, mut record) in members.iter() {
+            if record.address != address {
+                issues += 1;
+                record.address = address.clone();
+                members.set(address, record);
+            }
+        }
+
+        // A renounced wallet points OWNER at the contract itself, which
+        // never has a member record.
+        if owner != env.current_contract_address() {
+            match members.get(owner.clone()) {
+                Some(record) if record.role == FamilyRole::Owner => {}
+                Some(mut record) => {
+                    issues += 1;
+                    record.role = FamilyRole::Owner;
+                    members.set(owner.clone(), record);
+                }
+                None => {
+                    issues += 1;
+                    let now = env.ledger().timestamp();
+                    members.set(
+                        owner.clone(),
+                        Self::new_member(owner, FamilyRole::Owner, 0, now),
+                    );
+                }
+            }
+        }
+
+        let co_owners = Self::co_owners(env);
+        let mut kept = Vec::new(env);
+        for co_owner in co_owners.iter() {
+            match members.get(co_owner.clone()) {
+                Some(record) if record.role == FamilyRole::Owner => kept.push_back(co_owner),
+                _ => issues += 1,
+            }
+        }
+
+        let stats: Option<StorageStats> = env.storage().instance().get(&symbol_short!("STOR_STAT"));
+        let stale_stats = stats.is_some_and(|stats| stats.total_members != members.len());
+        if stale_stats {
+            issues += 1;
+        }
+
+        if repair && issues > 0 {
+            env.storage()
+                .instance()
+                .set(&symbol_short!("MEMBERS"), &members);
+            if kept.len() != co_owners.len() {
+                env.storage()
+                    .instance()
+                    .set(&symbol_short!("ADMINS"), &kept);
+            }
+            Self::update_storage_stats(env);
+        }
+        issues
+    }
+
     fn update_storage_stats(env: &Env) {
         let pending_txs: Map<u64, PendingTransaction> = env
             .storage()
@@ -1,9 +1,9 @@
 use super::*;
 use soroban_sdk::testutils::storage::Instance as _;
 use soroban_sdk::{
-    testutils::{Address as _, Ledger, LedgerInfo},
+    testutils::{Address as _, Events, Ledger, LedgerInfo},
     token::{StellarAssetClient, TokenClient},
-    vec, Env,
+    vec, Env, FromVal, IntoVal, TryFromVal, Val,
 };
 
 #[test]
@@ -1036,3 +1036,5513 @@ fn test_archive_ttl_extended_on_archive_transactions() {
         ttl
     );
 }
+
+#[test]
+fn test_get_frozen_count() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let member1 = Address::generate(&env);
+    let member2 = Address::generate(&env);
+    let member3 = Address::generate(&env);
+    client.init(
+        &owner,
+        &vec![&env, member1.clone(), member2.clone(), member3.clone()],
+    );
+    assert_eq!(client.get_frozen_count(), 0);
+
+    client.set_member_frozen(&owner, &member1, &true);
+    client.set_member_frozen(&owner, &member3, &true);
+    assert_eq!(client.get_frozen_count(), 2);
+    assert!(!client.check_spending_limit(&member1, &1));
+    assert!(client.check_spending_limit(&member2, &1));
+
+    client.set_member_frozen(&owner, &member1, &false);
+    assert_eq!(client.get_frozen_count(), 1);
+    assert!(client.check_spending_limit(&member1, &1));
+}
+
+#[test]
+fn test_set_member_frozen_rejects_owner_and_non_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let member1 = Address::generate(&env);
+    let member2 = Address::generate(&env);
+    client.init(&owner, &vec![&env, member1.clone(), member2.clone()]);
+
+    let result = client.try_set_member_frozen(&owner, &owner, &true);
+    assert_eq!(result, Err(Ok(Error::InvalidRole)));
+
+    let result = client.try_set_member_frozen(&member1, &member2, &true);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    assert_eq!(client.get_frozen_count(), 0);
+}
+
+#[test]
+fn test_record_spend_within_period_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &1000);
+    client.set_limit_period(&owner, &child, &LimitPeriod::Monthly);
+
+    assert!(client.record_spend(&child, &400));
+    assert!(client.record_spend(&child, &600));
+
+    let member = client.get_member(&child).unwrap();
+    assert_eq!(member.limit_period, LimitPeriod::Monthly);
+    assert_eq!(member.spent_in_period, 1000);
+}
+
+#[test]
+fn test_record_spend_exceeding_period_limit_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &1000);
+    client.set_limit_period(&owner, &child, &LimitPeriod::Weekly);
+
+    client.record_spend(&child, &700);
+    let result = client.try_record_spend(&child, &301);
+    assert_eq!(result, Err(Ok(Error::SpendingLimitExceeded)));
+    assert_eq!(client.get_member(&child).unwrap().spent_in_period, 700);
+}
+
+#[test]
+fn test_record_spend_window_resets_after_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &1000);
+    client.set_limit_period(&owner, &child, &LimitPeriod::Daily);
+
+    client.record_spend(&child, &1000);
+    assert_eq!(
+        client.try_record_spend(&child, &1),
+        Err(Ok(Error::SpendingLimitExceeded))
+    );
+
+    // Still inside the first day.
+    env.ledger().with_mut(|li| li.timestamp = 1_000 + 86_399);
+    assert!(client.try_record_spend(&child, &1).is_err());
+
+    // Two and a half days later the window has rolled over.
+    env.ledger()
+        .with_mut(|li| li.timestamp = 1_000 + 86_400 * 2 + 43_200);
+    assert!(client.record_spend(&child, &800));
+
+    let member = client.get_member(&child).unwrap();
+    assert_eq!(member.period_start, 1_000 + 86_400 * 2);
+    assert_eq!(member.spent_in_period, 800);
+}
+
+#[test]
+fn test_approval_quorum_scales_with_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let admin1 = Address::generate(&env);
+    let admin2 = Address::generate(&env);
+    let admin3 = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &admin1, &FamilyRole::Admin, &0);
+    client.add_member(&owner, &admin2, &FamilyRole::Admin, &0);
+    client.add_member(&owner, &admin3, &FamilyRole::Admin, &0);
+
+    let token_admin = Address::generate(&env);
+    let token_contract = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_client = TokenClient::new(&env, &token_contract.address());
+    StellarAssetClient::new(&env, &token_contract.address()).mint(&owner, &50_000);
+
+    let signers = vec![
+        &env,
+        owner.clone(),
+        admin1.clone(),
+        admin2.clone(),
+        admin3.clone(),
+    ];
+    client.configure_multisig(&owner, &TransactionType::LargeWithdrawal, &2, &signers, &50);
+    client.set_approval_tiers(
+        &owner,
+        &vec![
+            &env,
+            ApprovalTier {
+                min_amount: 0,
+                required_approvals: 1,
+            },
+            ApprovalTier {
+                min_amount: 1_000,
+                required_approvals: 2,
+            },
+            ApprovalTier {
+                min_amount: 10_000,
+                required_approvals: 3,
+            },
+        ],
+    );
+
+    assert_eq!(client.get_required_approvals(&100), 1);
+    assert_eq!(client.get_required_approvals(&5_000), 2);
+    assert_eq!(client.get_required_approvals(&10_000), 3);
+
+    // A 100 withdrawal is above the multisig limit but its tier needs only
+    // the proposer's own approval, so it executes immediately.
+    let recipient = Address::generate(&env);
+    let tx_id = client.withdraw(&owner, &token_contract.address(), &recipient, &100);
+    assert_eq!(tx_id, 0);
+    assert_eq!(token_client.balance(&recipient), 100);
+
+    // A 10,000 withdrawal needs three approvals.
+    let tx_id = client.withdraw(&owner, &token_contract.address(), &recipient, &10_000);
+    assert!(tx_id > 0);
+    client.sign_transaction(&admin1, &tx_id);
+    assert!(client.get_pending_transaction(&tx_id).is_some());
+    assert_eq!(token_client.balance(&recipient), 100);
+
+    client.sign_transaction(&admin2, &tx_id);
+    assert!(client.get_pending_transaction(&tx_id).is_none());
+    assert_eq!(token_client.balance(&recipient), 10_100);
+}
+
+#[test]
+#[should_panic(expected = "Approval tiers must be sorted by amount")]
+fn test_approval_tiers_must_be_sorted() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.set_approval_tiers(
+        &owner,
+        &vec![
+            &env,
+            ApprovalTier {
+                min_amount: 1_000,
+                required_approvals: 2,
+            },
+            ApprovalTier {
+                min_amount: 100,
+                required_approvals: 1,
+            },
+        ],
+    );
+}
+
+#[test]
+fn test_spend_accumulates_and_reduces_remaining() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &1000);
+    assert_eq!(client.get_remaining_limit(&child), Some(1000));
+
+    assert!(client.spend(&child, &250));
+    assert_eq!(client.get_remaining_limit(&child), Some(750));
+    assert!(client.spend(&child, &500));
+    assert_eq!(client.get_remaining_limit(&child), Some(250));
+
+    let member = client.get_member(&child).unwrap();
+    assert_eq!(member.spent, 750);
+}
+
+#[test]
+fn test_spend_over_limit_rejected_without_mutation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &1000);
+
+    assert!(client.spend(&child, &900));
+    assert!(!client.spend(&child, &101));
+    assert_eq!(client.get_member(&child).unwrap().spent, 900);
+    assert_eq!(client.get_remaining_limit(&child), Some(100));
+
+    assert!(client.spend(&child, &100));
+    assert_eq!(client.get_remaining_limit(&child), Some(0));
+}
+
+#[test]
+fn test_get_remaining_limit_unknown_and_unlimited() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+
+    assert_eq!(client.get_remaining_limit(&Address::generate(&env)), None);
+    assert_eq!(client.get_remaining_limit(&owner), Some(i128::MAX));
+    assert!(!client.spend(&Address::generate(&env), &1));
+}
+
+#[test]
+fn test_storage_footprint_grows_with_members_and_history() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let member1 = Address::generate(&env);
+    client.init(&owner, &vec![&env, member1.clone()]);
+
+    let (members, entries) = client.get_storage_footprint();
+    assert_eq!(members, 2);
+    assert_eq!(entries, 2);
+
+    // Each add_family_member adds a member and an access audit entry.
+    client.add_family_member(&owner, &Address::generate(&env), &FamilyRole::Member);
+    client.add_family_member(&owner, &Address::generate(&env), &FamilyRole::Viewer);
+    let (members, entries) = client.get_storage_footprint();
+    assert_eq!(members, 4);
+    assert_eq!(entries, 6);
+
+    client.set_role_expiry(&owner, &member1, &Some(10_000));
+    let (members, entries) = client.get_storage_footprint();
+    assert_eq!(members, 4);
+    assert_eq!(entries, 8);
+}
+
+#[test]
+fn test_two_step_ownership_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let member1 = Address::generate(&env);
+    client.init(&owner, &vec![&env, member1.clone()]);
+
+    assert!(client.propose_new_owner(&owner, &member1));
+    assert_eq!(client.get_pending_owner(), Some(member1.clone()));
+    // Nothing changes until the proposed owner accepts.
+    assert_eq!(client.get_owner(), owner);
+
+    assert!(client.accept_ownership(&member1));
+    assert_eq!(client.get_owner(), member1);
+    assert_eq!(client.get_pending_owner(), None);
+    assert_eq!(
+        client.get_family_member(&member1).unwrap().role,
+        FamilyRole::Owner
+    );
+    assert_eq!(
+        client.get_family_member(&owner).unwrap().role,
+        FamilyRole::Admin
+    );
+
+    let transferred = env.events().all().iter().any(|(_, topics, _)| {
+        topics
+            == vec![
+                &env,
+                symbol_short!("owner").into_val(&env),
+                OwnershipEvent::Transferred.into_val(&env),
+            ]
+    });
+    assert!(transferred);
+}
+
+#[test]
+#[should_panic(expected = "Caller is not the pending owner")]
+fn test_accept_ownership_wrong_address() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let member1 = Address::generate(&env);
+    let member2 = Address::generate(&env);
+    client.init(&owner, &vec![&env, member1.clone(), member2.clone()]);
+
+    client.propose_new_owner(&owner, &member1);
+    client.accept_ownership(&member2);
+}
+
+#[test]
+fn test_propose_new_owner_overwrites_pending() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+
+    client.propose_new_owner(&owner, &first);
+    client.propose_new_owner(&owner, &second);
+    assert_eq!(client.get_pending_owner(), Some(second.clone()));
+
+    assert!(client.try_accept_ownership(&first).is_err());
+    client.accept_ownership(&second);
+    assert_eq!(client.get_owner(), second);
+    // The new owner was not a member before and is added with the Owner role.
+    assert_eq!(
+        client.get_family_member(&second).unwrap().role,
+        FamilyRole::Owner
+    );
+}
+
+#[test]
+fn test_get_owner_returns_initialized_owner() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let member1 = Address::generate(&env);
+    client.init(&owner, &vec![&env, member1.clone()]);
+
+    assert_eq!(client.get_owner(), owner);
+    assert_ne!(client.get_owner(), member1);
+}
+
+#[test]
+fn test_get_owner_uninitialized() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    assert_eq!(
+        client.try_get_owner().err(),
+        Some(Ok(Error::NotInitialized.into()))
+    );
+}
+
+#[test]
+fn test_prune_history_removes_only_old_records() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env, child.clone()]);
+    client.set_history_retention(&owner, &1_000);
+
+    client.spend(&child, &10);
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+    client.spend(&child, &20);
+    assert_eq!(client.get_spend_history(&child).len(), 2);
+
+    env.ledger().with_mut(|li| li.timestamp = 2_500);
+    assert_eq!(client.prune_history(&owner, &child), 1);
+    let history = client.get_spend_history(&child);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history.get(0).unwrap().amount, 20);
+    assert_eq!(client.prune_history(&owner, &child), 0);
+
+    // Recording a new spend auto-prunes anything outside the window.
+    env.ledger().with_mut(|li| li.timestamp = 3_500);
+    client.spend(&child, &30);
+    let history = client.get_spend_history(&child);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history.get(0).unwrap().amount, 30);
+    assert_eq!(history.get(0).unwrap().timestamp, 3_500);
+}
+
+#[test]
+fn test_spend_history_kept_without_retention() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env, child.clone()]);
+
+    client.spend(&child, &10);
+    env.ledger().with_mut(|li| li.timestamp += 10_000_000);
+    client.spend(&child, &20);
+    assert_eq!(client.prune_history(&owner, &child), 0);
+    assert_eq!(client.get_spend_history(&child).len(), 2);
+}
+
+#[test]
+fn test_get_member_rank_by_spent() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let low = Address::generate(&env);
+    let mid = Address::generate(&env);
+    let high = Address::generate(&env);
+    client.init(&owner, &vec![&env, low.clone(), mid.clone(), high.clone()]);
+
+    client.spend(&low, &100);
+    client.spend(&mid, &500);
+    client.spend(&high, &900);
+    client.spend(&owner, &50);
+
+    assert_eq!(client.get_member_rank(&high), 1);
+    assert_eq!(client.get_member_rank(&mid), 2);
+    assert_eq!(client.get_member_rank(&low), 3);
+    assert_eq!(client.get_member_rank(&owner), 4);
+
+    assert_eq!(
+        client.try_get_member_rank(&Address::generate(&env)),
+        Err(Ok(Error::MemberNotFound))
+    );
+}
+
+#[test]
+fn test_get_member_rank_ties_ordered_by_address() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    client.init(&owner, &vec![&env, a.clone(), b.clone()]);
+
+    client.spend(&owner, &1_000);
+    client.spend(&a, &200);
+    client.spend(&b, &200);
+
+    let (first, second) = if a < b { (a, b) } else { (b, a) };
+    assert_eq!(client.get_member_rank(&first), 2);
+    assert_eq!(client.get_member_rank(&second), 3);
+}
+
+#[test]
+fn test_each_role_round_trips_through_get_member() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    assert_eq!(client.get_member(&owner).unwrap().role, FamilyRole::Owner);
+
+    for role in [FamilyRole::Admin, FamilyRole::Member, FamilyRole::Viewer] {
+        let addr = Address::generate(&env);
+        client.add_member(&owner, &addr, &role, &100);
+        let stored = client.get_member(&addr).unwrap();
+        assert_eq!(stored.role, role);
+        assert_eq!(stored.spending_limit, 100);
+    }
+}
+
+#[test]
+fn test_add_member_rejects_unknown_role_value() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+
+    // A raw discriminant that is not a FamilyRole fails argument conversion.
+    let result = env.try_invoke_contract::<bool, Error>(
+        &contract_id,
+        &Symbol::new(&env, "add_member"),
+        vec![
+            &env,
+            owner.into_val(&env),
+            member.into_val(&env),
+            99u32.into_val(&env),
+            0i128.into_val(&env),
+        ],
+    );
+    assert!(result.is_err());
+    assert!(client.get_member(&member).is_none());
+}
+
+#[test]
+fn test_ownership_transfer_with_confirmation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let successor = Address::generate(&env);
+    client.init(&owner, &vec![&env, successor.clone()]);
+    client.set_transfer_confirmation(&owner, &Some(3_600));
+
+    client.propose_new_owner(&owner, &successor);
+    assert!(!client.get_pending_ownership().unwrap().confirmed);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000 + 3_600);
+    client.confirm_transfer(&owner);
+    assert!(client.get_pending_ownership().unwrap().confirmed);
+
+    client.accept_ownership(&successor);
+    assert_eq!(client.get_owner(), successor);
+}
+
+#[test]
+#[should_panic(expected = "Ownership transfer not confirmed by owner")]
+fn test_accept_before_confirmation_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let successor = Address::generate(&env);
+    client.init(&owner, &vec![&env, successor.clone()]);
+    client.set_transfer_confirmation(&owner, &Some(3_600));
+
+    client.propose_new_owner(&owner, &successor);
+    client.accept_ownership(&successor);
+}
+
+#[test]
+#[should_panic(expected = "Transfer confirmation timelock not elapsed")]
+fn test_confirm_transfer_before_timelock_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let successor = Address::generate(&env);
+    client.init(&owner, &vec![&env, successor.clone()]);
+    client.set_transfer_confirmation(&owner, &Some(3_600));
+
+    client.propose_new_owner(&owner, &successor);
+    env.ledger().with_mut(|li| li.timestamp = 1_000 + 3_599);
+    client.confirm_transfer(&owner);
+}
+
+#[test]
+#[should_panic(expected = "No pending ownership transfer")]
+fn test_confirm_transfer_without_proposal_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.set_transfer_confirmation(&owner, &Some(0));
+
+    client.confirm_transfer(&owner);
+}
+
+#[test]
+fn test_admin_can_update_member_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let parent = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &parent, &FamilyRole::Admin, &0);
+    client.add_member(&owner, &child, &FamilyRole::Member, &100);
+
+    assert!(client.update_spending_limit(&parent, &child, &250, &None));
+    assert_eq!(client.get_member(&child).unwrap().spending_limit, 250);
+}
+
+#[test]
+fn test_member_cannot_update_limits() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    let sibling = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &100);
+    client.add_member(&owner, &sibling, &FamilyRole::Member, &100);
+
+    assert_eq!(
+        client.try_update_spending_limit(&child, &child, &1_000, &None),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_update_spending_limit(&child, &sibling, &1_000, &None),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(client.get_member(&child).unwrap().spending_limit, 100);
+}
+
+#[test]
+fn test_admin_cannot_update_peer_admin_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let parent1 = Address::generate(&env);
+    let parent2 = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &parent1, &FamilyRole::Admin, &500);
+    client.add_member(&owner, &parent2, &FamilyRole::Admin, &500);
+
+    assert_eq!(
+        client.try_update_spending_limit(&parent1, &parent2, &0, &None),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_update_spending_limit(&parent1, &owner, &1, &None),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    // The owner can still manage admins.
+    assert!(client.update_spending_limit(&owner, &parent2, &0, &None));
+}
+
+#[test]
+fn test_categorize_members_by_limit_usage() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let under = Address::generate(&env);
+    let at = Address::generate(&env);
+    let over = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &under, &FamilyRole::Member, &1_000);
+    client.add_member(&owner, &at, &FamilyRole::Member, &1_000);
+    client.add_member(&owner, &over, &FamilyRole::Member, &1_000);
+
+    client.spend(&under, &400);
+    client.spend(&at, &1_000);
+    client.spend(&over, &900);
+    // Lowering the limit below what was already spent puts a member over.
+    client.update_spending_limit(&owner, &over, &500, &None);
+
+    let (under_list, at_list, over_list) = client.categorize_members();
+    assert_eq!(under_list.len(), 2);
+    assert!(under_list.contains(&under));
+    assert!(under_list.contains(&owner));
+    assert_eq!(at_list, vec![&env, at.clone()]);
+    assert_eq!(over_list, vec![&env, over.clone()]);
+}
+
+#[test]
+fn test_inactive_member_cannot_spend_within_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &1_000);
+
+    client.set_member_active(&owner, &child, &false);
+    let member = client.get_member(&child).unwrap();
+    assert!(!member.active);
+    assert_eq!(member.spending_limit, 1_000);
+
+    assert!(!client.check_spending_limit(&child, &1));
+    assert!(!client.spend(&child, &1));
+    assert_eq!(
+        client.try_record_spend(&child, &1),
+        Err(Ok(Error::MemberInactive))
+    );
+
+    client.set_member_active(&owner, &child, &true);
+    assert!(client.check_spending_limit(&child, &1_000));
+    assert!(client.spend(&child, &1_000));
+}
+
+#[test]
+fn test_set_member_active_emits_status_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env, child.clone()]);
+
+    client.set_member_active(&owner, &child, &false);
+
+    let (_, _, data) = env
+        .events()
+        .all()
+        .iter()
+        .find(|(_, topics, _)| {
+            *topics
+                == vec![
+                    &env,
+                    symbol_short!("updated").into_val(&env),
+                    symbol_short!("status").into_val(&env),
+                    child.into_val(&env),
+                ]
+        })
+        .expect("status event not published");
+    let event: MemberStatusChangedEvent = data.into_val(&env);
+    assert_eq!(event.member, child);
+    assert!(!event.active);
+
+    assert_eq!(
+        client.try_set_member_active(&owner, &owner, &false),
+        Err(Ok(Error::InvalidRole))
+    );
+}
+
+#[test]
+fn test_limit_change_requires_reason_when_enabled() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &100);
+
+    client.set_require_change_reason(&owner, &true);
+    assert!(client.get_require_change_reason());
+
+    assert_eq!(
+        client.try_update_spending_limit(&owner, &child, &200, &None),
+        Err(Ok(Error::ReasonRequired))
+    );
+    assert_eq!(
+        client.try_update_limit_with_reason(&owner, &child, &200, &String::from_str(&env, "")),
+        Err(Ok(Error::ReasonRequired))
+    );
+    assert_eq!(client.get_member(&child).unwrap().spending_limit, 100);
+
+    let memo = String::from_str(&env, "term fees");
+    assert!(client.update_spending_limit(&owner, &child, &150, &Some(memo)));
+    assert_eq!(client.get_member(&child).unwrap().spending_limit, 150);
+
+    let reason = String::from_str(&env, "school trip");
+    assert!(client.update_limit_with_reason(&owner, &child, &200, &reason));
+    assert_eq!(client.get_member(&child).unwrap().spending_limit, 200);
+
+    let audit = client.get_access_audit(&1);
+    let entry = audit.get(0).unwrap();
+    assert_eq!(entry.operation, symbol_short!("upd_lim"));
+    assert_eq!(entry.target, Some(child.clone()));
+    assert_eq!(entry.reason, Some(reason));
+}
+
+#[test]
+fn test_limit_change_without_reason_allowed_by_default() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &100);
+
+    assert!(client.update_spending_limit(&owner, &child, &300, &None));
+    let entry = client.get_access_audit(&1).get(0).unwrap();
+    assert_eq!(entry.operation, symbol_short!("upd_lim"));
+    assert_eq!(entry.reason, None);
+}
+
+#[test]
+fn test_paused_wallet_rejects_mutations_but_allows_reads() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &100);
+
+    client.pause(&owner);
+    assert!(client.is_paused());
+
+    let newcomer = Address::generate(&env);
+    assert!(client
+        .try_add_member(&owner, &newcomer, &FamilyRole::Member, &100)
+        .is_err());
+    assert!(client
+        .try_update_spending_limit(&owner, &child, &500, &None)
+        .is_err());
+    assert!(client.try_spend(&child, &10).is_err());
+    assert!(client.try_record_spend(&child, &10).is_err());
+
+    // Reads stay available while paused.
+    assert_eq!(client.get_member(&child).unwrap().spending_limit, 100);
+    assert_eq!(client.get_owner(), owner);
+    assert!(client.check_spending_limit(&child, &10));
+
+    client.unpause(&owner);
+    assert!(client.add_member(&owner, &newcomer, &FamilyRole::Member, &100));
+    assert!(client.spend(&child, &10));
+}
+
+#[test]
+#[should_panic(expected = "Contract is paused")]
+fn test_paused_wallet_add_member_panics() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.pause(&owner);
+
+    client.add_member(&owner, &Address::generate(&env), &FamilyRole::Member, &100);
+}
+
+#[test]
+fn test_scheduled_pause_blocks_spends_inside_window_only() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &1_000);
+
+    client.schedule_pause(&owner, &2_000, &3_000);
+    assert_eq!(client.get_scheduled_pauses().len(), 1);
+
+    // Before the window.
+    assert!(client.spend(&child, &10));
+
+    // Inside the window.
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+    assert!(client.try_spend(&child, &10).is_err());
+    env.ledger().with_mut(|li| li.timestamp = 2_999);
+    assert!(client.try_record_spend(&child, &10).is_err());
+    // Non-spending operations are unaffected.
+    assert!(client.update_spending_limit(&owner, &child, &2_000, &None));
+
+    // After the window.
+    env.ledger().with_mut(|li| li.timestamp = 3_000);
+    assert!(client.spend(&child, &10));
+    assert!(!client.is_paused());
+}
+
+#[test]
+#[should_panic(expected = "Spending paused for scheduled window")]
+fn test_scheduled_pause_panics_inside_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env, child.clone()]);
+    client.schedule_pause(&owner, &1_000, &5_000);
+
+    client.spend(&child, &10);
+}
+
+#[test]
+fn test_clear_scheduled_pauses_and_window_validation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 10_000);
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env, child.clone()]);
+
+    assert!(client.try_schedule_pause(&owner, &20_000, &20_000).is_err());
+    assert!(client.try_schedule_pause(&owner, &1_000, &5_000).is_err());
+    assert!(client.try_schedule_pause(&child, &20_000, &30_000).is_err());
+
+    client.schedule_pause(&owner, &10_000, &30_000);
+    client.clear_scheduled_pauses(&owner);
+    assert_eq!(client.get_scheduled_pauses().len(), 0);
+    assert!(client.spend(&child, &10));
+}
+
+#[test]
+fn test_add_members_batch_adds_all() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    let c = Address::generate(&env);
+    let batch = vec![
+        &env,
+        MemberSpec {
+            address: a.clone(),
+            role: FamilyRole::Admin,
+            spending_limit: 0,
+        },
+        MemberSpec {
+            address: b.clone(),
+            role: FamilyRole::Member,
+            spending_limit: 500,
+        },
+        MemberSpec {
+            address: c.clone(),
+            role: FamilyRole::Viewer,
+            spending_limit: 50,
+        },
+    ];
+
+    assert_eq!(client.add_members_batch(&owner, &batch), 3);
+    assert_eq!(client.get_member(&a).unwrap().role, FamilyRole::Admin);
+    assert_eq!(client.get_member(&b).unwrap().spending_limit, 500);
+    assert_eq!(client.get_member(&c).unwrap().spending_limit, 50);
+}
+
+#[test]
+fn test_add_members_batch_reverts_on_invalid_entry() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    let c = Address::generate(&env);
+    let batch = vec![
+        &env,
+        MemberSpec {
+            address: a.clone(),
+            role: FamilyRole::Member,
+            spending_limit: 100,
+        },
+        MemberSpec {
+            address: b.clone(),
+            role: FamilyRole::Member,
+            spending_limit: -1,
+        },
+        MemberSpec {
+            address: c.clone(),
+            role: FamilyRole::Member,
+            spending_limit: 100,
+        },
+    ];
+
+    assert_eq!(
+        client.try_add_members_batch(&owner, &batch),
+        Err(Ok(Error::InvalidSpendingLimit))
+    );
+    assert!(client.get_member(&a).is_none());
+    assert!(client.get_member(&b).is_none());
+    assert!(client.get_member(&c).is_none());
+
+    let duplicate = vec![
+        &env,
+        MemberSpec {
+            address: a.clone(),
+            role: FamilyRole::Member,
+            spending_limit: 100,
+        },
+        MemberSpec {
+            address: a.clone(),
+            role: FamilyRole::Viewer,
+            spending_limit: 100,
+        },
+    ];
+    assert_eq!(
+        client.try_add_members_batch(&owner, &duplicate),
+        Err(Ok(Error::InvalidRole))
+    );
+    assert!(client.get_member(&a).is_none());
+}
+
+#[test]
+fn test_state_digest_tracks_member_updates() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &member, &FamilyRole::Member, &1000);
+
+    let first = client.state_digest();
+    client.get_member(&member);
+    client.get_owner();
+    assert_eq!(client.state_digest(), first);
+
+    client.update_spending_limit(&owner, &member, &2000, &None);
+    let second = client.state_digest();
+    assert_ne!(second, first);
+    assert_eq!(client.state_digest(), second);
+}
+
+#[test]
+fn test_get_members_paged() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    for _ in 0..4 {
+        client.add_member(&owner, &Address::generate(&env), &FamilyRole::Member, &100);
+    }
+
+    // Owner plus four members, in the same order a full read would give.
+    let all = client.get_members_paged(&0, &10);
+    assert_eq!(all.len(), 5);
+
+    let first = client.get_members_paged(&0, &2);
+    assert_eq!(first.len(), 2);
+    assert_eq!(first.get(0).unwrap().address, all.get(0).unwrap().address);
+    assert_eq!(first.get(1).unwrap().address, all.get(1).unwrap().address);
+
+    let middle = client.get_members_paged(&2, &2);
+    assert_eq!(middle.len(), 2);
+    assert_eq!(middle.get(0).unwrap().address, all.get(2).unwrap().address);
+    assert_eq!(middle.get(1).unwrap().address, all.get(3).unwrap().address);
+
+    let tail = client.get_members_paged(&4, &2);
+    assert_eq!(tail.len(), 1);
+    assert_eq!(tail.get(0).unwrap().address, all.get(4).unwrap().address);
+
+    assert_eq!(client.get_members_paged(&5, &2).len(), 0);
+    assert_eq!(client.get_members_paged(&100, &2).len(), 0);
+    assert_eq!(client.get_members_paged(&0, &0).len(), 0);
+}
+
+#[test]
+fn test_get_member_count() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    assert_eq!(client.get_member_count(), 0);
+
+    let owner = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    // The owner's own record is not counted.
+    assert_eq!(client.get_member_count(), 0);
+
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    let c = Address::generate(&env);
+    client.add_member(&owner, &a, &FamilyRole::Member, &100);
+    client.add_member(&owner, &b, &FamilyRole::Member, &100);
+    client.add_member(&owner, &c, &FamilyRole::Viewer, &0);
+    assert_eq!(client.get_member_count(), 3);
+
+    client.remove_family_member(&owner, &b);
+    assert_eq!(client.get_member_count(), 2);
+}
+
+#[test]
+fn test_max_recipients_blocks_new_recipient_but_allows_repeats() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &0);
+    client.configure_multisig(
+        &owner,
+        &TransactionType::LargeWithdrawal,
+        &1,
+        &vec![&env, owner.clone()],
+        &1000_0000000,
+    );
+
+    let token_admin = Address::generate(&env);
+    let token_contract = env.register_stellar_asset_contract_v2(token_admin);
+    let token = token_contract.address();
+    StellarAssetClient::new(&env, &token).mint(&child, &1000);
+
+    client.set_limit_period(&owner, &child, &LimitPeriod::Daily);
+    client.set_max_recipients(&owner, &child, &2);
+
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    let third = Address::generate(&env);
+    assert_eq!(client.withdraw(&child, &token, &first, &10), 0);
+    assert_eq!(client.withdraw(&child, &token, &second, &10), 0);
+    assert_eq!(client.get_period_recipients(&child).len(), 2);
+
+    // A third distinct recipient is over the cap; repeats are fine.
+    assert_eq!(
+        client.try_withdraw(&child, &token, &third, &10).err(),
+        Some(Ok(Error::RecipientLimitReached.into()))
+    );
+    assert_eq!(client.withdraw(&child, &token, &first, &10), 0);
+    assert_eq!(client.get_period_recipients(&child).len(), 2);
+
+    // A new window starts with a clean recipient set.
+    env.ledger().with_mut(|li| li.timestamp = 1_000 + 86_400);
+    assert_eq!(client.get_period_recipients(&child).len(), 0);
+    assert_eq!(client.withdraw(&child, &token, &third, &10), 0);
+
+    let balances = TokenClient::new(&env, &token);
+    assert_eq!(balances.balance(&first), 20);
+    assert_eq!(balances.balance(&third), 10);
+}
+
+#[test]
+fn test_proposed_withdrawal_takes_recipient_slot_only_on_execution() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &0);
+    client.configure_multisig(
+        &owner,
+        &TransactionType::LargeWithdrawal,
+        &1,
+        &vec![&env, owner.clone()],
+        &100,
+    );
+
+    let token_admin = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    StellarAssetClient::new(&env, &token).mint(&child, &1_000);
+    client.set_max_recipients(&owner, &child, &1);
+
+    // A large withdrawal only waits for approval, so it uses no slot.
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    let tx_id = client.withdraw(&child, &token, &first, &500);
+    assert!(tx_id > 0);
+    assert_eq!(client.get_period_recipients(&child).len(), 0);
+
+    assert_eq!(client.withdraw(&child, &token, &second, &10), 0);
+    assert_eq!(
+        client.get_period_recipients(&child),
+        vec![&env, second.clone()]
+    );
+
+    // Executing it now would pay a second distinct recipient.
+    assert_eq!(
+        client.try_sign_transaction(&owner, &tx_id).err(),
+        Some(Ok(Error::RecipientLimitReached.into()))
+    );
+    assert_eq!(TokenClient::new(&env, &token).balance(&first), 0);
+}
+
+#[test]
+fn test_set_max_recipients_requires_manager() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &100);
+
+    assert_eq!(
+        client.try_set_max_recipients(&child, &child, &5),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_max_recipients(&owner, &stranger, &5),
+        Err(Ok(Error::MemberNotFound))
+    );
+    assert!(client.set_max_recipients(&owner, &child, &5));
+    assert_eq!(client.get_member(&child).unwrap().max_recipients, 5);
+}
+
+#[test]
+fn test_get_resolved_member_config_applies_role_and_status_rules() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &admin, &FamilyRole::Admin, &500);
+    client.add_member(&owner, &child, &FamilyRole::Member, &300);
+
+    // The Admin role overrides the stored limit.
+    let resolved = client.get_resolved_member_config(&admin).unwrap();
+    assert!(resolved.unlimited);
+    assert_eq!(resolved.spending_limit, 500);
+    assert_eq!(resolved.remaining_limit, i128::MAX);
+    assert!(resolved.can_spend);
+
+    client.set_limit_period(&owner, &child, &LimitPeriod::Daily);
+    client.record_spend(&child, &100);
+    env.ledger()
+        .with_mut(|li| li.timestamp = 1_000 + 86_400 + 5);
+    let resolved = client.get_resolved_member_config(&child).unwrap();
+    assert!(!resolved.unlimited);
+    assert_eq!(resolved.limit_period, LimitPeriod::Daily);
+    // The window has rolled over even though nothing has been written.
+    assert_eq!(resolved.period_start, 1_000 + 86_400);
+    assert_eq!(resolved.remaining_limit, 300);
+    assert!(resolved.can_spend);
+
+    client.set_member_frozen(&owner, &child, &true);
+    assert!(!client.get_resolved_member_config(&child).unwrap().can_spend);
+    client.set_member_frozen(&owner, &child, &false);
+
+    client.set_role_expiry(&owner, &child, &Some(1_000 + 86_400 + 10));
+    env.ledger()
+        .with_mut(|li| li.timestamp = 1_000 + 86_400 + 10);
+    let resolved = client.get_resolved_member_config(&child).unwrap();
+    assert_eq!(resolved.role_expires_at, Some(1_000 + 86_400 + 10));
+    assert!(resolved.role_expired);
+    assert!(!resolved.can_spend);
+
+    assert!(client
+        .get_resolved_member_config(&Address::generate(&env))
+        .is_none());
+}
+
+#[test]
+fn test_member_events_carry_role_and_limits() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    let viewer = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+
+    let added_topics = |member: &Address| -> Vec<Val> {
+        vec![
+            &env,
+            symbol_short!("added").into_val(&env),
+            symbol_short!("member").into_val(&env),
+            member.into_val(&env),
+        ]
+    };
+    let limit_topics = vec![
+        &env,
+        symbol_short!("updated").into_val(&env),
+        symbol_short!("limit").into_val(&env),
+        child.into_val(&env),
+    ];
+
+    client.add_member(&owner, &child, &FamilyRole::Member, &250);
+    let event: MemberAddedEvent = env
+        .events()
+        .all()
+        .iter()
+        .rev()
+        .find(|(_, topics, _)| *topics == added_topics(&child))
+        .map(|(_, _, data)| data.into_val(&env))
+        .unwrap();
+    assert_eq!(event.member, child);
+    assert_eq!(event.role, FamilyRole::Member);
+    assert_eq!(event.spending_limit, 250);
+    assert_eq!(event.timestamp, 1_000);
+
+    client.update_spending_limit(&owner, &child, &400, &None);
+    let event: SpendingLimitUpdatedEvent = env
+        .events()
+        .all()
+        .iter()
+        .rev()
+        .find(|(_, topics, _)| *topics == limit_topics)
+        .map(|(_, _, data)| data.into_val(&env))
+        .unwrap();
+    assert_eq!(event.member, child);
+    assert_eq!(event.old_limit, 250);
+    assert_eq!(event.new_limit, 400);
+
+    // The legacy add path reports the new member too.
+    client.add_family_member(&owner, &viewer, &FamilyRole::Viewer);
+    let event: MemberAddedEvent = env
+        .events()
+        .all()
+        .iter()
+        .rev()
+        .find(|(_, topics, _)| *topics == added_topics(&viewer))
+        .map(|(_, _, data)| data.into_val(&env))
+        .unwrap();
+    assert_eq!(event.member, viewer);
+    assert_eq!(event.role, FamilyRole::Viewer);
+    assert_eq!(event.spending_limit, 0);
+}
+
+#[test]
+fn test_get_members_with_flags() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let frozen = Address::generate(&env);
+    let inactive = Address::generate(&env);
+    let normal = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &frozen, &FamilyRole::Member, &100);
+    client.add_member(&owner, &inactive, &FamilyRole::Member, &100);
+    client.add_member(&owner, &normal, &FamilyRole::Member, &100);
+
+    client.set_member_frozen(&owner, &frozen, &true);
+    client.set_member_active(&owner, &inactive, &false);
+
+    let rows = client.get_members_with_flags();
+    assert_eq!(rows.len(), 4);
+    for (member, is_frozen, is_active) in rows.iter() {
+        assert_eq!(is_frozen, member.address == frozen);
+        assert_eq!(is_active, member.address != inactive);
+        assert_eq!(is_frozen, member.frozen);
+        assert_eq!(is_active, member.active);
+    }
+}
+
+#[test]
+fn test_claim_allowance_accrues_per_interval() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &0);
+    client.set_allowance(&owner, &child, &50, &604_800, &0);
+
+    // Nothing has accrued before the first interval elapses.
+    env.ledger().with_mut(|li| li.timestamp = 1_000 + 604_799);
+    assert_eq!(client.claim_allowance(&child), 0);
+    assert_eq!(client.get_member(&child).unwrap().balance, 0);
+
+    // One interval.
+    env.ledger().with_mut(|li| li.timestamp = 1_000 + 604_800);
+    assert_eq!(client.claim_allowance(&child), 50);
+    assert_eq!(client.claim_allowance(&child), 0);
+
+    // Three more intervals plus a partial one, which carries over.
+    env.ledger()
+        .with_mut(|li| li.timestamp = 1_000 + 604_800 * 4 + 100);
+    assert_eq!(client.claim_allowance(&child), 150);
+    assert_eq!(client.get_member(&child).unwrap().balance, 200);
+    assert_eq!(
+        client.get_allowance(&child).unwrap().last_claim,
+        1_000 + 604_800 * 4
+    );
+}
+
+#[test]
+fn test_claim_allowance_respects_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    let admin = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &0);
+    client.add_member(&owner, &admin, &FamilyRole::Admin, &0);
+
+    assert_eq!(
+        client.try_claim_allowance(&child),
+        Err(Ok(Error::AllowanceNotSet))
+    );
+    assert_eq!(
+        client.try_set_allowance(&admin, &child, &50, &100, &0),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_allowance(&owner, &child, &50, &0, &0),
+        Err(Ok(Error::InvalidAmount))
+    );
+
+    client.set_allowance(&owner, &child, &50, &100, &120);
+    env.ledger().with_mut(|li| li.timestamp = 1_000 + 1_000);
+    assert_eq!(client.claim_allowance(&child), 120);
+    assert_eq!(client.get_member(&child).unwrap().balance, 120);
+}
+
+#[test]
+fn test_decline_alert_addressed_to_owner() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &100);
+
+    let alert_topics = vec![
+        &env,
+        symbol_short!("alert").into_val(&env),
+        owner.into_val(&env),
+    ];
+
+    // Alerts are off by default.
+    assert!(!client.get_decline_alerts());
+    assert!(!client.spend(&child, &500));
+    assert!(!env
+        .events()
+        .all()
+        .iter()
+        .any(|(_, topics, _)| topics == alert_topics));
+
+    client.set_decline_alerts(&owner, &true);
+    assert!(!client.spend(&child, &500));
+    let alert: OwnerAlertEvent = env
+        .events()
+        .all()
+        .iter()
+        .rev()
+        .find(|(_, topics, _)| *topics == alert_topics)
+        .map(|(_, _, data)| data.into_val(&env))
+        .unwrap();
+    assert_eq!(alert.member, child);
+    assert_eq!(alert.amount, 500);
+    assert_eq!(alert.reason, Error::SpendingLimitExceeded as u32);
+
+    // Accepted spends do not alert.
+    assert!(client.spend(&child, &50));
+    let alerts = env
+        .events()
+        .all()
+        .iter()
+        .filter(|(_, topics, _)| *topics == alert_topics)
+        .count();
+    assert_eq!(alerts, 1);
+}
+
+#[test]
+fn test_get_member_uninitialized() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    assert_eq!(
+        client.try_get_member(&Address::generate(&env)).err(),
+        Some(Ok(Error::NotInitialized.into()))
+    );
+}
+
+#[test]
+fn test_get_members_paged_uninitialized() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    assert_eq!(
+        client.try_get_members_paged(&0, &10).err(),
+        Some(Ok(Error::NotInitialized.into()))
+    );
+}
+
+#[test]
+fn test_check_spending_limit_uninitialized() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    assert_eq!(
+        client
+            .try_check_spending_limit(&Address::generate(&env), &100)
+            .err(),
+        Some(Ok(Error::NotInitialized.into()))
+    );
+}
+
+#[test]
+fn test_allocation_summary_splits_treasury() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    let teen = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &0);
+    client.add_member(&owner, &teen, &FamilyRole::Member, &0);
+
+    let token_admin = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    StellarAssetClient::new(&env, &token).mint(&contract_id, &1_000);
+    assert_eq!(client.get_allocation_summary(&token), (0, 1_000));
+
+    client.allocate_to_member(&owner, &token, &child, &300);
+    client.allocate_to_member(&owner, &token, &teen, &200);
+    assert_eq!(client.get_allocation_summary(&token), (500, 500));
+    assert_eq!(client.get_member(&child).unwrap().balance, 300);
+
+    assert_eq!(
+        client.try_allocate_to_member(&owner, &token, &teen, &501),
+        Err(Ok(Error::InsufficientBalance))
+    );
+    assert_eq!(
+        client.try_allocate_to_member(&child, &token, &teen, &10),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(client.get_allocation_summary(&token), (500, 500));
+}
+
+#[test]
+fn test_init_twice_returns_already_initialized() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    assert_eq!(
+        client.try_init(&Address::generate(&env), &vec![&env]).err(),
+        Some(Ok(Error::AlreadyInitialized.into()))
+    );
+    assert_eq!(client.get_owner(), owner);
+}
+
+#[test]
+fn test_uninitialized_calls_return_not_initialized() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let caller = Address::generate(&env);
+    let member = Address::generate(&env);
+    assert_eq!(
+        client.try_remove_family_member(&caller, &member).err(),
+        Some(Ok(Error::NotInitialized.into()))
+    );
+    assert_eq!(
+        client.try_get_family_member(&member).err(),
+        Some(Ok(Error::NotInitialized.into()))
+    );
+}
+
+#[test]
+fn test_transfer_balance_auto_enrolls_unknown_recipient() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    let newcomer = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &0);
+    client.set_allowance(&owner, &child, &100, &10, &0);
+    env.ledger().with_mut(|li| li.timestamp += 10);
+    client.claim_allowance(&child);
+
+    // Off by default: unknown recipients are rejected and nothing moves.
+    assert_eq!(
+        client.try_transfer_balance(&child, &newcomer, &40),
+        Err(Ok(Error::MemberNotFound))
+    );
+    assert!(client.get_member(&newcomer).is_none());
+    assert_eq!(client.get_member(&child).unwrap().balance, 100);
+
+    client.set_auto_enroll(
+        &owner,
+        &Some(AutoEnrollConfig {
+            role: FamilyRole::Viewer,
+            spending_limit: 25,
+        }),
+    );
+    assert!(client.transfer_balance(&child, &newcomer, &40));
+
+    let enrolled = client.get_member(&newcomer).unwrap();
+    assert_eq!(enrolled.role, FamilyRole::Viewer);
+    assert_eq!(enrolled.spending_limit, 25);
+    assert_eq!(enrolled.balance, 40);
+    assert_eq!(client.get_member(&child).unwrap().balance, 60);
+
+    // Turning it back off stops further enrollment.
+    client.set_auto_enroll(&owner, &None);
+    assert_eq!(
+        client.try_transfer_balance(&child, &Address::generate(&env), &10),
+        Err(Ok(Error::MemberNotFound))
+    );
+}
+
+#[test]
+fn test_transfer_balance_auto_enroll_applies_add_member_checks() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let guardian = Address::generate(&env);
+    let child = Address::generate(&env);
+    let newcomer = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &guardian, &FamilyRole::Guardian, &0);
+    client.add_member(&owner, &child, &FamilyRole::Member, &0);
+    client.set_allowance(&owner, &child, &100, &10, &0);
+    env.ledger().with_mut(|li| li.timestamp += 10);
+    client.claim_allowance(&child);
+    client.set_auto_enroll(
+        &owner,
+        &Some(AutoEnrollConfig {
+            role: FamilyRole::Viewer,
+            spending_limit: 25,
+        }),
+    );
+
+    client.set_limit_bounds(&owner, &50, &0);
+    assert_eq!(
+        client.try_transfer_balance(&child, &newcomer, &10),
+        Err(Ok(Error::LimitOutOfBounds))
+    );
+    client.set_limit_bounds(&owner, &0, &0);
+
+    client.flag_dispute(&guardian);
+    assert_eq!(
+        client.try_transfer_balance(&child, &newcomer, &10),
+        Err(Ok(Error::DisputeActive))
+    );
+    client.resolve_dispute(&owner);
+
+    client.renounce_ownership(&owner);
+    assert_eq!(
+        client.try_transfer_balance(&child, &newcomer, &10),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert!(client.get_member(&newcomer).is_none());
+    assert_eq!(client.get_member(&child).unwrap().balance, 100);
+}
+
+#[test]
+fn test_transfer_balance_between_members() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    let sibling = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &0);
+    client.add_member(&owner, &sibling, &FamilyRole::Member, &0);
+    client.set_allowance(&owner, &child, &30, &10, &0);
+    env.ledger().with_mut(|li| li.timestamp += 10);
+    client.claim_allowance(&child);
+
+    assert_eq!(
+        client.try_transfer_balance(&child, &sibling, &31),
+        Err(Ok(Error::InsufficientBalance))
+    );
+    // A self-transfer would credit the same record twice.
+    assert_eq!(
+        client.try_transfer_balance(&child, &child, &10),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(client.get_member(&child).unwrap().balance, 30);
+    assert!(client.transfer_balance(&child, &sibling, &30));
+    assert_eq!(client.get_member(&child).unwrap().balance, 0);
+    assert_eq!(client.get_member(&sibling).unwrap().balance, 30);
+    assert_eq!(
+        client.try_set_auto_enroll(
+            &owner,
+            &Some(AutoEnrollConfig {
+                role: FamilyRole::Owner,
+                spending_limit: 0,
+            })
+        ),
+        Err(Ok(Error::InvalidRole))
+    );
+}
+
+#[test]
+fn test_add_member_validates_role_and_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+
+    assert_eq!(
+        client.try_add_member(&owner, &member, &FamilyRole::Owner, &100),
+        Err(Ok(Error::InvalidRole))
+    );
+    assert_eq!(
+        client.try_add_member(&owner, &member, &FamilyRole::Member, &-1),
+        Err(Ok(Error::InvalidSpendingLimit))
+    );
+    assert!(client.get_member(&member).is_none());
+
+    assert!(client.add_member(&owner, &member, &FamilyRole::Member, &100));
+    assert_eq!(
+        client.try_add_member(&owner, &member, &FamilyRole::Viewer, &100),
+        Err(Ok(Error::InvalidRole))
+    );
+    assert_eq!(client.get_member(&member).unwrap().role, FamilyRole::Member);
+}
+
+#[test]
+fn test_get_spend_trend_buckets_by_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &1_000);
+    client.set_limit_period(&owner, &child, &LimitPeriod::Daily);
+
+    // Day 0.
+    client.record_spend(&child, &10);
+    // Day 1, two spends.
+    env.ledger().with_mut(|li| li.timestamp = 1_000 + 86_400);
+    client.record_spend(&child, &20);
+    assert!(client.spend(&child, &5));
+    // Day 2 is skipped; day 3.
+    env.ledger()
+        .with_mut(|li| li.timestamp = 1_000 + 86_400 * 3 + 60);
+    client.record_spend(&child, &40);
+
+    assert_eq!(
+        client.get_spend_trend(&child, &5),
+        vec![&env, 0, 10, 25, 0, 40]
+    );
+    assert_eq!(client.get_spend_trend(&child, &2), vec![&env, 0, 40]);
+    assert_eq!(client.get_spend_trend(&child, &0).len(), 0);
+    assert_eq!(client.get_spend_trend(&child, &100).len(), 12);
+    assert_eq!(
+        client.get_spend_trend(&Address::generate(&env), &3).len(),
+        0
+    );
+}
+
+#[test]
+fn test_limit_history_records_changes_in_order() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &100);
+    assert_eq!(client.get_limit_history(&child).len(), 0);
+
+    client.update_spending_limit(&owner, &child, &200, &None);
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+    client.update_limit_with_reason(&owner, &child, &150, &String::from_str(&env, "cut back"));
+
+    let history = client.get_limit_history(&child);
+    assert_eq!(history.len(), 2);
+    let first = history.get(0).unwrap();
+    assert_eq!(
+        (first.old_limit, first.new_limit, first.timestamp),
+        (100, 200, 1_000)
+    );
+    let second = history.get(1).unwrap();
+    assert_eq!(
+        (second.old_limit, second.new_limit, second.timestamp),
+        (200, 150, 2_000)
+    );
+}
+
+#[test]
+fn test_limit_history_drops_oldest_past_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &0);
+
+    for limit in 1..=21i128 {
+        client.update_spending_limit(&owner, &child, &limit, &None);
+    }
+
+    let history = client.get_limit_history(&child);
+    assert_eq!(history.len(), 20);
+    // The 0 -> 1 change was dropped; the oldest kept is 1 -> 2.
+    assert_eq!(history.get(0).unwrap().old_limit, 1);
+    assert_eq!(history.get(19).unwrap().new_limit, 21);
+}
+
+#[test]
+fn test_wallet_monthly_cap_without_rollover() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    let teen = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &0);
+    client.add_member(&owner, &teen, &FamilyRole::Member, &0);
+    client.set_wallet_monthly_cap(&owner, &100, &0);
+
+    // The cap is shared across members.
+    client.record_spend(&child, &60);
+    assert_eq!(
+        client.try_record_spend(&teen, &50),
+        Err(Ok(Error::WalletCapExceeded))
+    );
+    assert!(!client.spend(&teen, &50));
+    assert!(client.spend(&teen, &40));
+    assert_eq!(client.get_wallet_monthly_remaining(), Some(0));
+
+    // A new month starts with a fresh budget and nothing carried over.
+    env.ledger()
+        .with_mut(|li| li.timestamp = 1_000 + 30 * 86_400);
+    assert_eq!(client.get_wallet_monthly_remaining(), Some(100));
+    assert_eq!(
+        client.try_record_spend(&child, &101),
+        Err(Ok(Error::WalletCapExceeded))
+    );
+    client.record_spend(&child, &100);
+}
+
+#[test]
+fn test_wallet_monthly_cap_rolls_over_unused_budget() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &0);
+    client.set_wallet_monthly_cap(&owner, &100, &30);
+
+    client.record_spend(&child, &60);
+
+    // 40 was unused; only 30 carries over.
+    env.ledger()
+        .with_mut(|li| li.timestamp = 1_000 + 30 * 86_400 + 5);
+    let cap = client.get_wallet_monthly_cap().unwrap();
+    assert_eq!(cap.carried, 30);
+    assert_eq!(cap.period_start, 1_000 + 30 * 86_400);
+    assert_eq!(client.get_wallet_monthly_remaining(), Some(130));
+    client.record_spend(&child, &130);
+    assert_eq!(
+        client.try_record_spend(&child, &1),
+        Err(Ok(Error::WalletCapExceeded))
+    );
+
+    // Removing the cap lifts the restriction.
+    client.set_wallet_monthly_cap(&owner, &0, &0);
+    assert_eq!(client.get_wallet_monthly_remaining(), None);
+    client.record_spend(&child, &1);
+}
+
+#[test]
+fn test_large_child_withdrawal_needs_two_parent_approvals() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let parent = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &parent, &FamilyRole::Admin, &0);
+    client.add_member(&owner, &child, &FamilyRole::Member, &0);
+    client.configure_multisig(
+        &owner,
+        &TransactionType::LargeWithdrawal,
+        &2,
+        &vec![&env, owner.clone(), parent.clone()],
+        &100,
+    );
+
+    let token_admin = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    let token_client = TokenClient::new(&env, &token);
+    StellarAssetClient::new(&env, &token).mint(&child, &1_000);
+    let recipient = Address::generate(&env);
+
+    // At or below the threshold the spend goes straight through.
+    assert_eq!(client.withdraw(&child, &token, &recipient, &100), 0);
+    assert_eq!(token_client.balance(&recipient), 100);
+
+    // Above it, the child's proposal carries no approval of its own.
+    let tx_id = client.withdraw(&child, &token, &recipient, &500);
+    assert!(tx_id > 0);
+    assert_eq!(
+        client
+            .get_pending_transaction(&tx_id)
+            .unwrap()
+            .signatures
+            .len(),
+        0
+    );
+
+    client.sign_transaction(&owner, &tx_id);
+    assert!(client.get_pending_transaction(&tx_id).is_some());
+    assert_eq!(token_client.balance(&recipient), 100);
+
+    client.sign_transaction(&parent, &tx_id);
+    assert!(client.get_pending_transaction(&tx_id).is_none());
+    assert_eq!(token_client.balance(&recipient), 600);
+    assert_eq!(token_client.balance(&child), 400);
+}
+
+#[test]
+fn test_preview_limit_change_reports_over_limit_without_writing() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &500);
+    client.record_spend(&child, &300);
+
+    // A cut below what has already been spent puts the member over.
+    assert_eq!(client.preview_limit_change(&child, &200), (true, 0));
+    // A raise leaves them under, with more headroom.
+    assert_eq!(client.preview_limit_change(&child, &800), (false, 500));
+    // 0 means unlimited.
+    assert_eq!(client.preview_limit_change(&child, &0), (false, i128::MAX));
+
+    // Nothing was written.
+    assert_eq!(client.get_member(&child).unwrap().spending_limit, 500);
+    assert_eq!(client.get_remaining_limit(&child), Some(200));
+    assert_eq!(client.get_limit_history(&child).len(), 0);
+
+    assert_eq!(
+        client.try_preview_limit_change(&Address::generate(&env), &100),
+        Err(Ok(Error::MemberNotFound))
+    );
+}
+
+#[test]
+fn test_renounce_ownership_locks_owner_functions() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    let admin = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &100);
+    client.add_member(&owner, &admin, &FamilyRole::Admin, &0);
+    client.propose_new_owner(&owner, &child);
+
+    assert!(client.renounce_ownership(&owner));
+
+    let renounced = env.events().all().iter().any(|(_, topics, _)| {
+        topics
+            == vec![
+                &env,
+                symbol_short!("owner").into_val(&env),
+                OwnershipEvent::Renounced.into_val(&env),
+            ]
+    });
+    assert!(renounced);
+
+    // Owner-gated calls revert for the former owner.
+    assert_eq!(
+        client.try_add_member(&owner, &Address::generate(&env), &FamilyRole::Member, &10),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert!(client.try_set_decline_alerts(&owner, &true).is_err());
+    assert!(client
+        .try_propose_new_owner(&owner, &Address::generate(&env))
+        .is_err());
+    // Admins can no longer add members either.
+    assert_eq!(
+        client.try_add_member(&admin, &Address::generate(&env), &FamilyRole::Member, &10),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_add_members_batch(
+            &admin,
+            &vec![
+                &env,
+                MemberSpec {
+                    address: Address::generate(&env),
+                    role: FamilyRole::Member,
+                    spending_limit: 10,
+                },
+            ],
+        ),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert!(client
+        .try_add_family_member(&admin, &Address::generate(&env), &FamilyRole::Member)
+        .is_err());
+    // The pending transfer was dropped.
+    assert_eq!(client.get_pending_owner(), None);
+    assert!(client.try_accept_ownership(&child).is_err());
+
+    // Reads still work.
+    assert_eq!(client.get_owner(), contract_id);
+    assert_eq!(client.get_member_count(), 3);
+    assert_eq!(client.get_members_paged(&0, &10).len(), 3);
+    assert_eq!(client.get_member(&owner).unwrap().role, FamilyRole::Member);
+    assert_eq!(client.get_member(&child).unwrap().spending_limit, 100);
+}
+
+#[test]
+#[should_panic(expected = "Only Owner can renounce ownership")]
+fn test_renounce_ownership_requires_owner() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let admin = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &admin, &FamilyRole::Admin, &0);
+
+    client.renounce_ownership(&admin);
+}
+
+#[test]
+fn test_designated_approver_gates_large_withdrawal() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let grandparent = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &grandparent, &FamilyRole::Member, &0);
+    client.add_member(&owner, &child, &FamilyRole::Member, &0);
+    client.configure_multisig(
+        &owner,
+        &TransactionType::LargeWithdrawal,
+        &1,
+        &vec![&env, owner.clone()],
+        &100,
+    );
+    client.set_approver(&owner, &child, &Some(grandparent.clone()));
+
+    let token_admin = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    let token_client = TokenClient::new(&env, &token);
+    StellarAssetClient::new(&env, &token).mint(&child, &1_000);
+    let recipient = Address::generate(&env);
+
+    let tx_id = client.withdraw(&child, &token, &recipient, &500);
+    assert!(tx_id > 0);
+
+    // The owner's signature meets the quorum, but the approver has not
+    // signed yet.
+    client.sign_transaction(&owner, &tx_id);
+    assert!(client.get_pending_transaction(&tx_id).is_some());
+    assert_eq!(token_client.balance(&recipient), 0);
+
+    client.sign_transaction(&grandparent, &tx_id);
+    assert!(client.get_pending_transaction(&tx_id).is_none());
+    assert_eq!(token_client.balance(&recipient), 500);
+}
+
+#[test]
+#[should_panic(expected = "Signer not authorized for this transaction type")]
+fn test_unrelated_member_cannot_act_as_approver() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let grandparent = Address::generate(&env);
+    let sibling = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &grandparent, &FamilyRole::Member, &0);
+    client.add_member(&owner, &sibling, &FamilyRole::Member, &0);
+    client.add_member(&owner, &child, &FamilyRole::Member, &0);
+    client.configure_multisig(
+        &owner,
+        &TransactionType::LargeWithdrawal,
+        &1,
+        &vec![&env, owner.clone()],
+        &100,
+    );
+    client.set_approver(&owner, &child, &Some(grandparent.clone()));
+
+    let token_admin = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    StellarAssetClient::new(&env, &token).mint(&child, &1_000);
+
+    let tx_id = client.withdraw(&child, &token, &Address::generate(&env), &500);
+    client.sign_transaction(&sibling, &tx_id);
+}
+
+#[test]
+fn test_set_approver_validation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &admin, &FamilyRole::Admin, &0);
+    client.add_member(&owner, &child, &FamilyRole::Member, &0);
+
+    assert_eq!(
+        client.try_set_approver(&admin, &child, &Some(admin.clone())),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_approver(&owner, &child, &Some(child.clone())),
+        Err(Ok(Error::InvalidSigner))
+    );
+    assert_eq!(
+        client.try_set_approver(&owner, &child, &Some(Address::generate(&env))),
+        Err(Ok(Error::MemberNotFound))
+    );
+
+    client.set_approver(&owner, &child, &Some(admin.clone()));
+    assert_eq!(client.get_member(&child).unwrap().approver, Some(admin));
+    client.set_approver(&owner, &child, &None);
+    assert_eq!(client.get_member(&child).unwrap().approver, None);
+}
+
+#[test]
+fn test_get_members_by_role_filters_subset() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let child_a = Address::generate(&env);
+    let child_b = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &admin, &FamilyRole::Admin, &0);
+    client.add_member(&owner, &child_a, &FamilyRole::Member, &100);
+    client.add_member(&owner, &child_b, &FamilyRole::Member, &100);
+
+    let owners = client.get_members_by_role(&FamilyRole::Owner);
+    assert_eq!(owners.len(), 1);
+    assert_eq!(owners.get(0).unwrap().address, owner);
+
+    let admins = client.get_members_by_role(&FamilyRole::Admin);
+    assert_eq!(admins.len(), 1);
+    assert_eq!(admins.get(0).unwrap().address, admin);
+
+    let children = client.get_members_by_role(&FamilyRole::Member);
+    assert_eq!(children.len(), 2);
+    for m in children.iter() {
+        assert!(m.address == child_a || m.address == child_b);
+        assert_eq!(m.role, FamilyRole::Member);
+    }
+
+    assert_eq!(client.get_members_by_role(&FamilyRole::Viewer).len(), 0);
+}
+
+#[test]
+fn test_get_members_by_role_uninitialized_is_empty() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    assert_eq!(client.get_members_by_role(&FamilyRole::Member).len(), 0);
+}
+
+#[test]
+fn test_get_pending_operations_aggregates_all_kinds() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let member1 = Address::generate(&env);
+    let member2 = Address::generate(&env);
+    client.init(&owner, &vec![&env, member1.clone(), member2.clone()]);
+    assert_eq!(client.get_pending_operations().len(), 0);
+
+    let signers = vec![&env, owner.clone(), member1.clone(), member2.clone()];
+    client.configure_multisig(
+        &owner,
+        &TransactionType::SplitConfigChange,
+        &3,
+        &signers,
+        &0,
+    );
+    client.configure_multisig(&owner, &TransactionType::RoleChange, &3, &signers, &0);
+    let split_tx = client.propose_split_config_change(&owner, &40, &30, &20, &10);
+    let role_tx = client.propose_role_change(&owner, &member2, &FamilyRole::Admin);
+
+    client.set_transfer_confirmation(&owner, &Some(3_600));
+    client.propose_new_owner(&owner, &member1);
+
+    client.set_withdrawal_delay(&owner, &600);
+    let request_id = client.request_withdrawal(&member2, &50);
+
+    let ops = client.get_pending_operations();
+    assert_eq!(ops.len(), 4);
+
+    let transfer = ops.get(0).unwrap();
+    assert_eq!(transfer.kind, PendingOpKind::OwnershipTransfer);
+    assert_eq!(transfer.subject, member1);
+    assert_eq!(transfer.created_at, 1_000);
+    assert_eq!(transfer.effective_at, 4_600);
+
+    let first = ops.get(1).unwrap();
+    assert_eq!(first.kind, PendingOpKind::Transaction);
+    assert_eq!(first.id, split_tx);
+    assert_eq!(first.subject, owner);
+    assert!(first.expires_at > first.created_at);
+    assert_eq!(ops.get(2).unwrap().id, role_tx);
+
+    let withdrawal = ops.get(3).unwrap();
+    assert_eq!(withdrawal.kind, PendingOpKind::WithdrawalRequest);
+    assert_eq!(withdrawal.id, request_id);
+    assert_eq!(withdrawal.subject, member2);
+    assert_eq!(withdrawal.created_at, 1_000);
+    assert_eq!(withdrawal.effective_at, 1_600);
+    assert_eq!(withdrawal.expires_at, 0);
+
+    client.sign_transaction(&member1, &split_tx);
+    client.sign_transaction(&member2, &split_tx);
+    client.cancel_withdrawal(&member2, &request_id);
+    let ops = client.get_pending_operations();
+    assert_eq!(ops.len(), 2);
+    assert_eq!(ops.get(1).unwrap().id, role_tx);
+}
+
+#[test]
+fn test_increase_and_decrease_spending_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &1_000);
+
+    assert_eq!(client.increase_spending_limit(&owner, &child, &500), 1_500);
+    assert_eq!(client.get_member(&child).unwrap().spending_limit, 1_500);
+
+    assert_eq!(client.decrease_spending_limit(&owner, &child, &1_499), 1);
+    assert_eq!(client.get_member(&child).unwrap().spending_limit, 1);
+    assert_eq!(client.get_limit_history(&child).len(), 2);
+}
+
+#[test]
+fn test_decrease_spending_limit_rejects_non_positive_result() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    let unlimited = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &1_000);
+    client.add_member(&owner, &unlimited, &FamilyRole::Member, &0);
+
+    assert_eq!(
+        client.try_decrease_spending_limit(&owner, &child, &1_000),
+        Err(Ok(Error::InvalidSpendingLimit))
+    );
+    assert_eq!(
+        client.try_decrease_spending_limit(&owner, &child, &5_000),
+        Err(Ok(Error::InvalidSpendingLimit))
+    );
+    assert_eq!(
+        client.try_increase_spending_limit(&owner, &child, &0),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(
+        client.try_increase_spending_limit(&owner, &unlimited, &100),
+        Err(Ok(Error::InvalidSpendingLimit))
+    );
+    assert_eq!(
+        client.try_increase_spending_limit(&child, &child, &100),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(client.get_member(&child).unwrap().spending_limit, 1_000);
+}
+
+#[test]
+fn test_auto_topup_on_deposit_resets_spend_and_tx_count() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    let sibling = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &100);
+    client.add_member(&owner, &sibling, &FamilyRole::Member, &100);
+    client.set_auto_topup_on_deposit(&owner, &child, &true);
+    client.set_max_tx_count(&owner, &child, &1);
+
+    let token_admin = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    StellarAssetClient::new(&env, &token).mint(&contract_id, &1_000);
+
+    client.record_spend(&child, &100);
+    client.record_spend(&sibling, &100);
+    assert_eq!(
+        client.try_record_spend(&child, &1),
+        Err(Ok(Error::SpendingLimitExceeded))
+    );
+
+    client.allocate_to_member(&owner, &token, &child, &50);
+    client.allocate_to_member(&owner, &token, &sibling, &50);
+
+    let topped_up = client.get_member(&child).unwrap();
+    assert_eq!(topped_up.spent_in_period, 0);
+    assert_eq!(topped_up.spent, 0);
+    assert_eq!(topped_up.tx_count, 0);
+    client.record_spend(&child, &100);
+
+    // Members without the flag keep their spend.
+    assert_eq!(client.get_member(&sibling).unwrap().spent_in_period, 100);
+    assert_eq!(
+        client.try_record_spend(&sibling, &1),
+        Err(Ok(Error::SpendingLimitExceeded))
+    );
+
+    assert_eq!(
+        client.try_set_auto_topup_on_deposit(&sibling, &child, &false),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Only Owner can upgrade")]
+fn test_upgrade_rejects_non_owner() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let admin = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &admin, &FamilyRole::Admin, &0);
+
+    client.upgrade(&admin, &BytesN::from_array(&env, &[7u8; 32]));
+}
+
+#[test]
+fn test_token_limits_enforced_independently() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &0);
+
+    let usdc = Address::generate(&env);
+    let xlm = Address::generate(&env);
+    let unlisted = Address::generate(&env);
+    client.set_token_limit(&owner, &child, &usdc, &100);
+    client.set_token_limit(&owner, &child, &xlm, &5_000);
+
+    assert!(client.check_token_spending_limit(&child, &usdc, &100));
+    assert!(!client.check_token_spending_limit(&child, &usdc, &101));
+    assert!(client.check_token_spending_limit(&child, &xlm, &5_000));
+    assert!(!client.check_token_spending_limit(&child, &xlm, &5_001));
+    assert!(!client.check_token_spending_limit(&child, &unlisted, &1));
+
+    // Owners and admins are never restricted, as with `check_spending_limit`.
+    assert!(client.check_token_spending_limit(&owner, &unlisted, &1_000_000));
+
+    let limits = client.get_token_limits(&child);
+    assert_eq!(limits.len(), 2);
+    assert_eq!(limits.get(usdc.clone()), Some(100));
+}
+
+#[test]
+fn test_set_token_limit_validation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &0);
+    let token = Address::generate(&env);
+
+    assert_eq!(
+        client.try_set_token_limit(&owner, &child, &token, &-1),
+        Err(Ok(Error::InvalidSpendingLimit))
+    );
+    assert_eq!(
+        client.try_set_token_limit(&child, &child, &token, &100),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_token_limit(&owner, &Address::generate(&env), &token, &100),
+        Err(Ok(Error::MemberNotFound))
+    );
+
+    client.set_token_limit(&owner, &child, &token, &0);
+    assert!(client.check_token_spending_limit(&child, &token, &1_000_000));
+}
+
+#[test]
+fn test_block_increase_when_over_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &100);
+    client.set_limit_period(&owner, &child, &LimitPeriod::Daily);
+    client.record_spend(&child, &100);
+
+    // Lowering the limit leaves the child over it for this window.
+    client.update_spending_limit(&owner, &child, &50, &None);
+    client.set_block_increase_when_over(&owner, &true);
+    assert!(client.get_block_increase_when_over());
+
+    assert_eq!(
+        client.try_update_spending_limit(&owner, &child, &200, &None),
+        Err(Ok(Error::MemberOverLimit))
+    );
+    assert_eq!(
+        client.try_update_spending_limit(&owner, &child, &0, &None),
+        Err(Ok(Error::MemberOverLimit))
+    );
+    assert_eq!(
+        client.try_increase_spending_limit(&owner, &child, &10),
+        Err(Ok(Error::MemberOverLimit))
+    );
+    // Decreases remain allowed.
+    client.update_spending_limit(&owner, &child, &40, &None);
+    assert_eq!(client.get_member(&child).unwrap().spending_limit, 40);
+
+    // A new window brings the child back within limits.
+    env.ledger().with_mut(|li| li.timestamp = 1_000 + 86_400);
+    client.update_spending_limit(&owner, &child, &200, &None);
+    assert_eq!(client.get_member(&child).unwrap().spending_limit, 200);
+}
+
+#[test]
+fn test_limit_increase_allowed_when_over_without_flag() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &100);
+    client.record_spend(&child, &100);
+    client.update_spending_limit(&owner, &child, &50, &None);
+
+    client.update_spending_limit(&owner, &child, &200, &None);
+    assert_eq!(client.get_member(&child).unwrap().spending_limit, 200);
+}
+
+#[test]
+fn test_spend_token_moves_funds_within_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &300);
+
+    let token_admin = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    let token_client = TokenClient::new(&env, &token);
+    StellarAssetClient::new(&env, &token).mint(&contract_id, &1_000);
+    let shop = Address::generate(&env);
+    client.set_token_limit(&owner, &child, &token, &0);
+
+    assert!(client.spend_token(&child, &token, &250, &shop));
+    assert_eq!(token_client.balance(&shop), 250);
+    assert_eq!(token_client.balance(&contract_id), 750);
+
+    let record = client.get_member(&child).unwrap();
+    assert_eq!(record.spent_in_period, 250);
+    assert_eq!(record.spent, 250);
+}
+
+#[test]
+fn test_spend_token_over_limit_reverts_without_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    let admin = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &300);
+    client.add_member(&owner, &admin, &FamilyRole::Admin, &0);
+
+    let token_admin = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    let token_client = TokenClient::new(&env, &token);
+    StellarAssetClient::new(&env, &token).mint(&contract_id, &1_000);
+    let shop = Address::generate(&env);
+
+    // No cap configured for this token: unlisted tokens are rejected.
+    assert_eq!(
+        client.try_spend_token(&child, &token, &1, &shop),
+        Err(Ok(Error::SpendingLimitExceeded))
+    );
+    client.set_token_limit(&owner, &child, &token, &200);
+    assert_eq!(
+        client.try_spend_token(&child, &token, &201, &shop),
+        Err(Ok(Error::SpendingLimitExceeded))
+    );
+    client.set_token_limit(&owner, &child, &token, &0);
+    assert_eq!(
+        client.try_spend_token(&child, &token, &301, &shop),
+        Err(Ok(Error::SpendingLimitExceeded))
+    );
+    assert_eq!(
+        client.try_spend_token(&admin, &token, &1_001, &shop),
+        Err(Ok(Error::InsufficientBalance))
+    );
+    assert_eq!(
+        client.try_spend_token(&child, &token, &0, &shop),
+        Err(Ok(Error::InvalidAmount))
+    );
+
+    assert_eq!(token_client.balance(&shop), 0);
+    assert_eq!(token_client.balance(&contract_id), 1_000);
+    assert_eq!(client.get_member(&child).unwrap().spent, 0);
+}
+
+#[test]
+fn test_member_audit_is_chronological() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 100);
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    let other = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &500);
+    client.add_member(&owner, &other, &FamilyRole::Member, &500);
+
+    env.ledger().with_mut(|li| li.timestamp = 200);
+    client.record_spend(&child, &50);
+    env.ledger().with_mut(|li| li.timestamp = 300);
+    client.update_spending_limit(&owner, &child, &800, &None);
+    env.ledger().with_mut(|li| li.timestamp = 400);
+    client.set_member_frozen(&owner, &child, &true);
+    client.set_member_frozen(&owner, &other, &true);
+    env.ledger().with_mut(|li| li.timestamp = 500);
+    client.set_member_frozen(&owner, &child, &false);
+    env.ledger().with_mut(|li| li.timestamp = 600);
+    client.record_spend(&child, &70);
+
+    let trail = client.get_member_audit(&child);
+    assert_eq!(trail.len(), 6);
+    let expected = [
+        (MemberAuditKind::Created, 100, 500, 0),
+        (MemberAuditKind::Spend, 200, 50, 0),
+        (MemberAuditKind::LimitChanged, 300, 800, 500),
+        (MemberAuditKind::Frozen, 400, 0, 0),
+        (MemberAuditKind::Unfrozen, 500, 0, 0),
+        (MemberAuditKind::Spend, 600, 70, 0),
+    ];
+    for (i, (kind, timestamp, value, previous)) in expected.iter().enumerate() {
+        let entry = trail.get(i as u32).unwrap();
+        assert_eq!(entry.kind, *kind);
+        assert_eq!(entry.timestamp, *timestamp);
+        assert_eq!(entry.value, *value);
+        assert_eq!(entry.previous, *previous);
+    }
+
+    assert_eq!(client.get_member_audit(&Address::generate(&env)).len(), 0);
+}
+
+#[test]
+fn test_initialize_with_members_adds_all() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let parent = Address::generate(&env);
+    let child = Address::generate(&env);
+    let specs = vec![
+        &env,
+        MemberSpec {
+            address: parent.clone(),
+            role: FamilyRole::Admin,
+            spending_limit: 0,
+        },
+        MemberSpec {
+            address: child.clone(),
+            role: FamilyRole::Member,
+            spending_limit: 250,
+        },
+    ];
+
+    assert!(client.initialize_with_members(&owner, &specs));
+    assert_eq!(client.get_owner(), owner);
+    assert_eq!(client.get_member_count(), 2);
+    assert_eq!(client.get_member(&parent).unwrap().role, FamilyRole::Admin);
+    assert_eq!(client.get_member(&child).unwrap().spending_limit, 250);
+
+    assert_eq!(
+        client.try_initialize_with_members(&owner, &vec![&env]),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+}
+
+#[test]
+fn test_initialize_with_members_reverts_on_invalid_entry() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    let specs = vec![
+        &env,
+        MemberSpec {
+            address: child.clone(),
+            role: FamilyRole::Member,
+            spending_limit: 100,
+        },
+        MemberSpec {
+            address: Address::generate(&env),
+            role: FamilyRole::Member,
+            spending_limit: -1,
+        },
+    ];
+    assert_eq!(
+        client.try_initialize_with_members(&owner, &specs),
+        Err(Ok(Error::InvalidSpendingLimit))
+    );
+
+    let owner_as_member = vec![
+        &env,
+        MemberSpec {
+            address: owner.clone(),
+            role: FamilyRole::Admin,
+            spending_limit: 0,
+        },
+    ];
+    assert_eq!(
+        client.try_initialize_with_members(&owner, &owner_as_member),
+        Err(Ok(Error::InvalidRole))
+    );
+
+    assert_eq!(client.get_member_count(), 0);
+    assert!(client.try_get_owner().is_err());
+}
+
+#[test]
+fn test_wallet_rate_limit_blocks_until_window_resets() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    let teen = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &0);
+    client.add_member(&owner, &teen, &FamilyRole::Member, &0);
+    client.set_wallet_rate_limit(&owner, &3, &3_600);
+
+    client.record_spend(&child, &1);
+    assert!(client.spend(&teen, &1));
+    client.record_spend(&child, &1);
+    assert_eq!(client.get_wallet_rate_limit().unwrap().count, 3);
+
+    assert!(client.try_record_spend(&teen, &1).is_err());
+    assert!(client.try_spend(&child, &1).is_err());
+    assert_eq!(client.get_member(&teen).unwrap().spent, 1);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000 + 3_600);
+    assert_eq!(client.get_wallet_rate_limit().unwrap().count, 0);
+    client.record_spend(&teen, &1);
+    assert_eq!(client.get_member(&teen).unwrap().spent, 2);
+}
+
+#[test]
+#[should_panic(expected = "Wallet rate limit exceeded")]
+fn test_wallet_rate_limit_panics_with_message() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &0);
+    client.set_wallet_rate_limit(&owner, &1, &60);
+
+    client.record_spend(&child, &1);
+    client.record_spend(&child, &1);
+}
+
+#[test]
+fn test_wallet_rate_limit_configuration() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &0);
+
+    assert_eq!(
+        client.try_set_wallet_rate_limit(&owner, &5, &0),
+        Err(Ok(Error::InvalidAmount))
+    );
+    client.set_wallet_rate_limit(&owner, &1, &60);
+    client.record_spend(&child, &1);
+    client.set_wallet_rate_limit(&owner, &0, &0);
+    assert!(client.get_wallet_rate_limit().is_none());
+    client.record_spend(&child, &1);
+}
+
+#[test]
+fn test_init_emits_initialized_event_once() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    client.init(&owner, &vec![&env, Address::generate(&env)]);
+
+    let topics: Vec<Val> = vec![
+        &env,
+        symbol_short!("family").into_val(&env),
+        FamilyWalletEvent::Initialized.into_val(&env),
+    ];
+    let events = env.events().all();
+    let mut initialized = 0;
+    for (event_contract, event_topics, data) in events.iter() {
+        if event_topics == topics {
+            initialized += 1;
+            assert_eq!(event_contract, contract_id);
+            assert_eq!(Address::from_val(&env, &data), owner);
+        }
+    }
+    assert_eq!(initialized, 1);
+}
+
+#[test]
+#[should_panic(expected = "Arithmetic overflow")]
+fn test_spend_accumulation_overflow_panics() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &0);
+
+    client.record_spend(&child, &(i128::MAX - 10));
+    assert_eq!(client.get_member(&child).unwrap().spent, i128::MAX - 10);
+
+    // Would wrap to a negative total without the overflow guard.
+    client.record_spend(&child, &20);
+}
+
+#[test]
+#[should_panic(expected = "Arithmetic overflow")]
+fn test_allowance_accrual_overflow_panics() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &0);
+    client.set_allowance(&owner, &child, &(i128::MAX / 2 + 1), &10, &0);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_020);
+    client.claim_allowance(&child);
+}
+
+#[test]
+fn test_get_member_or_default() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 500);
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &750);
+
+    let member = client.get_member_or_default(&child);
+    assert_eq!(member.address, child);
+    assert_eq!(member.role, FamilyRole::Member);
+    assert_eq!(member.spending_limit, 750);
+    assert_eq!(member.added_at, 500);
+    assert!(member.active);
+
+    let stranger = Address::generate(&env);
+    let default = client.get_member_or_default(&stranger);
+    assert_eq!(default.address, stranger);
+    assert_eq!(default.spending_limit, 0);
+    assert_eq!(default.role, FamilyRole::Viewer);
+    assert_eq!(default.added_at, 0);
+    assert!(!default.active);
+    assert!(!client.check_spending_limit(&stranger, &1));
+}
+
+#[test]
+fn test_custom_ttl_config_applied_on_next_mutation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set(LedgerInfo {
+        protocol_version: 20,
+        sequence_number: 100,
+        timestamp: 1000,
+        network_id: [0; 32],
+        base_reserve: 10,
+        min_temp_entry_ttl: 100,
+        min_persistent_entry_ttl: 100,
+        max_entry_ttl: 700_000,
+    });
+
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    assert_eq!(
+        client.get_ttl_config(),
+        TtlConfig {
+            threshold: 17_280,
+            bump_amount: 518_400,
+        }
+    );
+
+    // A threshold above the current TTL (518,400) forces the next bump.
+    client.set_ttl_config(&owner, &600_000, &650_000);
+    client.add_member(&owner, &Address::generate(&env), &FamilyRole::Member, &0);
+
+    let ttl = env.as_contract(&contract_id, || env.storage().instance().get_ttl());
+    assert_eq!(ttl, 650_000);
+}
+
+#[test]
+fn test_set_ttl_config_owner_only_and_validated() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let admin = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &admin, &FamilyRole::Admin, &0);
+
+    assert_eq!(
+        client.try_set_ttl_config(&admin, &1_000, &100_000),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_ttl_config(&owner, &100_000, &100_000),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(
+        client.try_set_ttl_config(&owner, &1_000, &u32::MAX),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(client.get_ttl_config().bump_amount, 518_400);
+}
+
+#[test]
+fn test_bump_extends_ttl_without_auth() {
+    let env = Env::default();
+    env.ledger().set(LedgerInfo {
+        protocol_version: 20,
+        sequence_number: 100,
+        timestamp: 1000,
+        network_id: [0; 32],
+        base_reserve: 10,
+        min_temp_entry_ttl: 100,
+        min_persistent_entry_ttl: 100,
+        max_entry_ttl: 700_000,
+    });
+
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let member = Address::generate(&env);
+    env.mock_all_auths();
+    client.init(&owner, &vec![&env, member.clone()]);
+    let digest = client.state_digest();
+
+    env.ledger().set(LedgerInfo {
+        protocol_version: 20,
+        sequence_number: 510_000,
+        timestamp: 500_000,
+        network_id: [0; 32],
+        base_reserve: 10,
+        min_temp_entry_ttl: 100,
+        min_persistent_entry_ttl: 100,
+        max_entry_ttl: 700_000,
+    });
+
+    // No auths mocked: `bump` must not require any.
+    env.set_auths(&[]);
+    assert!(client.bump());
+    assert!(env.auths().is_empty());
+
+    // The only observable side effect besides the TTL is the event.
+    let topics: Vec<Val> = vec![
+        &env,
+        symbol_short!("family").into_val(&env),
+        FamilyWalletEvent::TtlExtended.into_val(&env),
+    ];
+    let (_, _, data) = env
+        .events()
+        .all()
+        .iter()
+        .rev()
+        .find(|(_, event_topics, _)| *event_topics == topics)
+        .expect("bump did not publish TtlExtended");
+    let event: TtlExtendedEvent = data.into_val(&env);
+    assert_eq!(event.bump_amount, 518_400);
+    assert_eq!(event.ledger, 510_000);
+
+    let ttl = env.as_contract(&contract_id, || env.storage().instance().get_ttl());
+    assert_eq!(ttl, 518_400);
+    assert_eq!(client.state_digest(), digest);
+    assert_eq!(client.get_owner(), owner);
+    assert_eq!(client.get_member_count(), 1);
+}
+
+#[test]
+fn test_transfer_limit_reallocates_budget() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let older = Address::generate(&env);
+    let younger = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &older, &FamilyRole::Member, &1_000);
+    client.add_member(&owner, &younger, &FamilyRole::Member, &200);
+
+    assert!(client.transfer_limit(&owner, &older, &younger, &300));
+    assert_eq!(client.get_member(&older).unwrap().spending_limit, 700);
+    assert_eq!(client.get_member(&younger).unwrap().spending_limit, 500);
+}
+
+#[test]
+fn test_transfer_limit_rejects_over_draw() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let older = Address::generate(&env);
+    let younger = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &older, &FamilyRole::Member, &1_000);
+    client.add_member(&owner, &younger, &FamilyRole::Member, &200);
+    client.record_spend(&older, &600);
+
+    // Would leave a non-positive limit.
+    assert_eq!(
+        client.try_transfer_limit(&owner, &older, &younger, &1_000),
+        Err(Ok(Error::InvalidSpendingLimit))
+    );
+    // Would drop below the 600 already spent.
+    assert_eq!(
+        client.try_transfer_limit(&owner, &older, &younger, &401),
+        Err(Ok(Error::InvalidSpendingLimit))
+    );
+    assert_eq!(
+        client.try_transfer_limit(&younger, &older, &younger, &100),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    assert_eq!(client.get_member(&older).unwrap().spending_limit, 1_000);
+    assert_eq!(client.get_member(&younger).unwrap().spending_limit, 200);
+
+    client.transfer_limit(&owner, &older, &younger, &400);
+    assert_eq!(client.get_member(&older).unwrap().spending_limit, 600);
+}
+
+#[test]
+fn test_transfer_limit_requires_both_members() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &1_000);
+
+    assert_eq!(
+        client.try_transfer_limit(&owner, &child, &stranger, &100),
+        Err(Ok(Error::MemberNotFound))
+    );
+    assert_eq!(
+        client.try_transfer_limit(&owner, &stranger, &child, &100),
+        Err(Ok(Error::MemberNotFound))
+    );
+    assert_eq!(client.get_member(&child).unwrap().spending_limit, 1_000);
+}
+
+#[test]
+fn test_category_limits_enforced_with_fallback() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &300);
+
+    let groceries = String::from_str(&env, "groceries");
+    let school = String::from_str(&env, "school");
+    let games = String::from_str(&env, "entertainment");
+    client.set_category_limit(&owner, &child, &groceries, &100);
+    client.set_category_limit(&owner, &child, &school, &1_000);
+
+    assert!(client.check_category_spending(&child, &groceries, &100));
+    assert!(!client.check_category_spending(&child, &groceries, &101));
+    // A category cap may exceed the overall limit.
+    assert!(client.check_category_spending(&child, &school, &1_000));
+    assert!(!client.check_category_spending(&child, &school, &1_001));
+
+    // Unconfigured categories fall back to `spending_limit`.
+    assert!(client.check_category_spending(&child, &games, &300));
+    assert!(!client.check_category_spending(&child, &games, &301));
+
+    assert_eq!(client.get_category_limits(&child).len(), 2);
+    assert_eq!(
+        client.try_set_category_limit(&child, &child, &games, &50),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_category_limit(&owner, &child, &games, &-1),
+        Err(Ok(Error::InvalidSpendingLimit))
+    );
+}
+
+#[test]
+fn test_update_spending_limit_returning_previous() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &1_000);
+
+    assert_eq!(
+        client.update_spending_limit_returning(&owner, &child, &2_000),
+        Some(1_000)
+    );
+    assert_eq!(
+        client.update_spending_limit_returning(&owner, &child, &500),
+        Some(2_000)
+    );
+    assert_eq!(client.get_member(&child).unwrap().spending_limit, 500);
+
+    assert_eq!(
+        client.update_spending_limit_returning(&owner, &Address::generate(&env), &100),
+        None
+    );
+    assert_eq!(
+        client.try_update_spending_limit_returning(&child, &child, &100),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_is_member() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    assert!(!client.is_member(&owner));
+
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &100);
+    assert!(client.is_member(&child));
+    assert!(client.is_member(&owner));
+    assert!(!client.is_member(&Address::generate(&env)));
+}
+
+#[test]
+fn test_co_owner_shares_owner_rights() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let partner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env, partner.clone()]);
+
+    client.add_admin(&owner, &partner);
+    assert_eq!(client.get_admins(), vec![&env, partner.clone()]);
+    assert_eq!(client.get_member(&partner).unwrap().role, FamilyRole::Owner);
+
+    // Owner-gated calls accept the co-owner.
+    client.add_member(&partner, &child, &FamilyRole::Member, &100);
+    assert!(client.set_decline_alerts(&partner, &true));
+    client.set_approver(&partner, &child, &Some(partner.clone()));
+    assert!(client.remove_family_member(&partner, &child));
+    assert!(client.get_member(&child).is_none());
+}
+
+#[test]
+fn test_removed_co_owner_loses_rights() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let partner = Address::generate(&env);
+    client.init(&owner, &vec![&env, partner.clone()]);
+    client.add_admin(&owner, &partner);
+
+    client.remove_admin(&owner, &partner);
+    assert_eq!(client.get_admins().len(), 0);
+    assert_eq!(
+        client.get_member(&partner).unwrap().role,
+        FamilyRole::Member
+    );
+    assert_eq!(
+        client.try_add_member(
+            &partner,
+            &Address::generate(&env),
+            &FamilyRole::Member,
+            &100
+        ),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert!(client.try_set_decline_alerts(&partner, &true).is_err());
+}
+
+#[test]
+fn test_co_owner_management_validation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let partner = Address::generate(&env);
+    let other = Address::generate(&env);
+    client.init(&owner, &vec![&env, partner.clone(), other.clone()]);
+    client.add_admin(&owner, &partner);
+
+    assert_eq!(
+        client.try_remove_admin(&owner, &owner),
+        Err(Ok(Error::InvalidRole))
+    );
+    assert_eq!(
+        client.try_add_admin(&owner, &partner),
+        Err(Ok(Error::InvalidRole))
+    );
+    // Only the root owner manages co-owners.
+    assert_eq!(
+        client.try_add_admin(&partner, &other),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_remove_admin(&partner, &owner),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_add_admin(&owner, &Address::generate(&env)),
+        Err(Ok(Error::MemberNotFound))
+    );
+    assert_eq!(
+        client.try_remove_admin(&owner, &other),
+        Err(Ok(Error::MemberNotFound))
+    );
+    assert_eq!(client.get_owner(), owner);
+}
+
+#[test]
+#[should_panic(expected = "Cannot remove owner")]
+fn test_co_owner_cannot_be_removed_as_member() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let partner = Address::generate(&env);
+    client.init(&owner, &vec![&env, partner.clone()]);
+    client.add_admin(&owner, &partner);
+
+    client.remove_family_member(&owner, &partner);
+}
+
+#[test]
+fn test_evaluate_spend_distinguishes_outcomes() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    let frozen = Address::generate(&env);
+    let inactive = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &100);
+    client.add_member(&owner, &frozen, &FamilyRole::Member, &100);
+    client.add_member(&owner, &inactive, &FamilyRole::Member, &100);
+    client.set_member_frozen(&owner, &frozen, &true);
+    client.set_member_active(&owner, &inactive, &false);
+
+    assert_eq!(
+        client.evaluate_spend(&child, &100),
+        SpendCheckResult::Allowed
+    );
+    assert_eq!(
+        client.evaluate_spend(&child, &101),
+        SpendCheckResult::OverLimit
+    );
+    assert_eq!(
+        client.evaluate_spend(&Address::generate(&env), &1),
+        SpendCheckResult::MemberNotFound
+    );
+    assert_eq!(
+        client.evaluate_spend(&frozen, &1),
+        SpendCheckResult::MemberInactive
+    );
+    assert_eq!(
+        client.evaluate_spend(&inactive, &1),
+        SpendCheckResult::MemberInactive
+    );
+    assert_eq!(
+        client.evaluate_spend(&child, &-1),
+        SpendCheckResult::InvalidAmount
+    );
+    assert_eq!(
+        client.evaluate_spend(&owner, &1_000_000),
+        SpendCheckResult::Allowed
+    );
+
+    // The boolean check stays in step.
+    assert!(client.check_spending_limit(&child, &100));
+    assert!(!client.check_spending_limit(&child, &101));
+}
+
+#[test]
+fn test_reset_spent_restores_full_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &member, &FamilyRole::Member, &1_000);
+    client.record_spend(&member, &700);
+    assert_eq!(client.get_remaining_limit(&member), Some(300));
+
+    assert!(client.reset_spent(&owner, &member));
+
+    let record = client.get_member(&member).unwrap();
+    assert_eq!(record.spent, 0);
+    assert_eq!(record.spent_in_period, 0);
+    assert_eq!(client.get_remaining_limit(&member), Some(1_000));
+
+    let topics: Vec<Val> = vec![
+        &env,
+        symbol_short!("spent").into_val(&env),
+        symbol_short!("reset").into_val(&env),
+        member.into_val(&env),
+    ];
+    let reset = env
+        .events()
+        .all()
+        .iter()
+        .any(|(_, event_topics, _)| event_topics == topics);
+    assert!(reset);
+}
+
+#[test]
+fn test_reset_spent_missing_member_returns_false() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+
+    assert!(!client.reset_spent(&owner, &Address::generate(&env)));
+}
+
+#[test]
+#[should_panic(expected = "Only Owner can reset spent")]
+fn test_reset_spent_requires_owner() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &admin, &FamilyRole::Admin, &0);
+    client.add_member(&owner, &member, &FamilyRole::Member, &1_000);
+
+    client.reset_spent(&admin, &member);
+}
+
+#[test]
+fn test_get_summary_aggregates_wallet_state() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &Address::generate(&env), &FamilyRole::Admin, &0);
+    client.add_member(
+        &owner,
+        &Address::generate(&env),
+        &FamilyRole::Member,
+        &1_000,
+    );
+    client.add_member(&owner, &Address::generate(&env), &FamilyRole::Member, &250);
+    client.pause(&owner);
+
+    let summary = client.get_summary();
+    assert_eq!(summary.owner, owner);
+    assert_eq!(summary.member_count, 3);
+    assert_eq!(summary.member_count, client.get_member_count());
+    assert!(summary.paused);
+    assert_eq!(summary.total_limit, 1_250);
+}
+
+#[test]
+fn test_limit_bounds_reject_out_of_range_limits() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    assert!(client.set_limit_bounds(&owner, &100, &1_000));
+
+    assert_eq!(
+        client.try_add_member(&owner, &member, &FamilyRole::Member, &50),
+        Err(Ok(Error::LimitOutOfBounds))
+    );
+    assert_eq!(
+        client.try_add_member(&owner, &member, &FamilyRole::Member, &5_000),
+        Err(Ok(Error::LimitOutOfBounds))
+    );
+    // Unlimited would exceed the ceiling.
+    assert_eq!(
+        client.try_add_member(&owner, &member, &FamilyRole::Member, &0),
+        Err(Ok(Error::LimitOutOfBounds))
+    );
+    assert!(client.add_member(&owner, &member, &FamilyRole::Member, &500));
+
+    assert_eq!(
+        client.try_update_spending_limit(&owner, &member, &99, &None),
+        Err(Ok(Error::LimitOutOfBounds))
+    );
+    assert_eq!(
+        client.try_update_spending_limit(&owner, &member, &1_001, &None),
+        Err(Ok(Error::LimitOutOfBounds))
+    );
+    assert_eq!(client.get_member(&member).unwrap().spending_limit, 500);
+
+    assert!(client.update_spending_limit(&owner, &member, &1_000, &None));
+    assert_eq!(client.get_member(&member).unwrap().spending_limit, 1_000);
+}
+
+#[test]
+fn test_limit_bounds_unset_and_validation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let admin = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    assert!(client.get_limit_bounds().is_none());
+
+    assert_eq!(
+        client.try_set_limit_bounds(&owner, &500, &100),
+        Err(Ok(Error::InvalidSpendingLimit))
+    );
+    client.add_member(&owner, &admin, &FamilyRole::Admin, &0);
+    assert_eq!(
+        client.try_set_limit_bounds(&admin, &100, &1_000),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    client.set_limit_bounds(&owner, &100, &1_000);
+    let bounds = client.get_limit_bounds().unwrap();
+    assert_eq!(bounds.min_limit, 100);
+    assert_eq!(bounds.max_limit, 1_000);
+
+    client.set_limit_bounds(&owner, &0, &0);
+    assert!(client.get_limit_bounds().is_none());
+    let member = Address::generate(&env);
+    assert!(client.add_member(&owner, &member, &FamilyRole::Member, &1));
+}
+
+#[test]
+fn test_withdrawal_request_blocked_until_delay_elapses() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &member, &FamilyRole::Member, &1_000);
+    client.set_withdrawal_delay(&owner, &3_600);
+
+    let id = client.request_withdrawal(&member, &400);
+    let request = client.get_withdrawal_request(&id).unwrap();
+    assert_eq!(request.amount, 400);
+    assert_eq!(request.executable_at, request.requested_at + 3_600);
+
+    assert_eq!(
+        client.try_execute_withdrawal(&member, &id),
+        Err(Ok(Error::WithdrawalDelayActive))
+    );
+    assert_eq!(client.get_member(&member).unwrap().spent, 0);
+
+    let mut ledger = env.ledger().get();
+    ledger.timestamp += 3_600;
+    env.ledger().set(ledger);
+
+    assert!(client.execute_withdrawal(&member, &id));
+    assert_eq!(client.get_member(&member).unwrap().spent_in_period, 400);
+    assert!(client.get_withdrawal_request(&id).is_none());
+    assert_eq!(
+        client.try_execute_withdrawal(&member, &id),
+        Err(Ok(Error::TransactionNotFound))
+    );
+}
+
+#[test]
+fn test_withdrawal_request_cancelled_by_owner() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let member = Address::generate(&env);
+    let other = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &member, &FamilyRole::Member, &1_000);
+    client.add_member(&owner, &other, &FamilyRole::Member, &1_000);
+    client.set_withdrawal_delay(&owner, &3_600);
+
+    let id = client.request_withdrawal(&member, &400);
+    assert_eq!(
+        client.try_cancel_withdrawal(&other, &id),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert!(client.cancel_withdrawal(&owner, &id));
+    assert!(client.get_withdrawal_request(&id).is_none());
+
+    let mut ledger = env.ledger().get();
+    ledger.timestamp += 3_600;
+    env.ledger().set(ledger);
+    assert_eq!(
+        client.try_execute_withdrawal(&member, &id),
+        Err(Ok(Error::TransactionNotFound))
+    );
+    assert_eq!(client.get_member(&member).unwrap().spent, 0);
+}
+
+#[test]
+fn test_withdrawal_execution_still_checks_limits() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &member, &FamilyRole::Member, &500);
+
+    let id = client.request_withdrawal(&member, &400);
+    client.record_spend(&member, &200);
+    assert_eq!(
+        client.try_execute_withdrawal(&member, &id),
+        Err(Ok(Error::SpendingLimitExceeded))
+    );
+    assert!(client.get_withdrawal_request(&id).is_some());
+}
+
+#[test]
+fn test_get_members_over_utilization() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let low = Address::generate(&env);
+    let at_threshold = Address::generate(&env);
+    let over = Address::generate(&env);
+    let unlimited = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &low, &FamilyRole::Member, &1_000);
+    client.add_member(&owner, &at_threshold, &FamilyRole::Member, &1_000);
+    client.add_member(&owner, &over, &FamilyRole::Member, &300);
+    client.add_member(&owner, &unlimited, &FamilyRole::Member, &0);
+
+    client.record_spend(&low, &100);
+    client.record_spend(&at_threshold, &800);
+    client.record_spend(&over, &290);
+    client.record_spend(&unlimited, &5_000);
+    client.record_spend(&owner, &5_000);
+
+    let flagged = client.get_members_over_utilization(&80);
+    assert_eq!(flagged.len(), 2);
+    assert!(flagged.contains(&at_threshold));
+    assert!(flagged.contains(&over));
+
+    assert_eq!(client.get_members_over_utilization(&0).len(), 3);
+    assert_eq!(client.get_members_over_utilization(&97).len(), 0);
+}
+
+#[test]
+fn test_wallet_name_set_and_get() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    assert_eq!(client.get_wallet_name(), None);
+
+    let name = String::from_str(&env, "Smith Household");
+    assert!(client.set_wallet_name(&owner, &name));
+    assert_eq!(client.get_wallet_name(), Some(name));
+
+    client.set_wallet_name(&owner, &String::from_str(&env, ""));
+    assert_eq!(client.get_wallet_name(), None);
+}
+
+#[test]
+fn test_wallet_name_owner_only() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let admin = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &admin, &FamilyRole::Admin, &0);
+    client.set_wallet_name(&owner, &String::from_str(&env, "Smith Household"));
+
+    assert_eq!(
+        client.try_set_wallet_name(&admin, &String::from_str(&env, "Renamed")),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.get_wallet_name(),
+        Some(String::from_str(&env, "Smith Household"))
+    );
+}
+
+#[test]
+fn test_get_member_private_owner_and_self_access() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &admin, &FamilyRole::Admin, &0);
+    client.add_member(&owner, &member, &FamilyRole::Member, &750);
+
+    assert_eq!(
+        client.get_member_private(&owner, &member).spending_limit,
+        750
+    );
+    assert_eq!(
+        client.get_member_private(&admin, &member).role,
+        FamilyRole::Member
+    );
+    assert_eq!(client.get_member_private(&member, &member).address, member);
+    assert!(matches!(
+        client.try_get_member_private(&owner, &Address::generate(&env)),
+        Err(Ok(Error::MemberNotFound))
+    ));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1)")]
+fn test_get_member_private_rejects_third_party() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let sibling = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &sibling, &FamilyRole::Member, &500);
+    client.add_member(&owner, &member, &FamilyRole::Member, &750);
+
+    client.get_member_private(&sibling, &member);
+}
+
+#[test]
+fn test_member_events_filterable_by_address_topic() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &alice, &FamilyRole::Member, &100);
+    client.add_member(&owner, &bob, &FamilyRole::Member, &200);
+    client.update_spending_limit(&owner, &alice, &150, &None);
+    client.set_member_active(&owner, &bob, &false);
+
+    let mut alice_events = Vec::<Symbol>::new(&env);
+    for (_, topics, _) in env.events().all().iter() {
+        if topics.len() == 3
+            && Address::try_from_val(&env, &topics.get(2).unwrap()) == Ok(alice.clone())
+        {
+            alice_events.push_back(Symbol::from_val(&env, &topics.get(1).unwrap()));
+        }
+    }
+    assert_eq!(
+        alice_events,
+        vec![&env, symbol_short!("member"), symbol_short!("limit")]
+    );
+}
+
+#[test]
+fn test_clear_all_members_keeps_owner() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let admin = Address::generate(&env);
+    client.init(
+        &owner,
+        &vec![&env, Address::generate(&env), Address::generate(&env)],
+    );
+    client.add_member(&owner, &admin, &FamilyRole::Admin, &0);
+    assert_eq!(client.get_member_count(), 3);
+
+    assert_eq!(client.clear_all_members(&owner), 3);
+    assert_eq!(client.get_member_count(), 0);
+    assert!(client.get_member(&admin).is_none());
+    assert_eq!(client.get_owner(), owner);
+    assert_eq!(client.get_member(&owner).unwrap().role, FamilyRole::Owner);
+
+    let topics: Vec<Val> = vec![
+        &env,
+        symbol_short!("family").into_val(&env),
+        FamilyWalletEvent::MembersCleared.into_val(&env),
+    ];
+    let cleared = env
+        .events()
+        .all()
+        .iter()
+        .filter(|(_, event_topics, _)| *event_topics == topics)
+        .count();
+    assert_eq!(cleared, 1);
+
+    let child = Address::generate(&env);
+    assert!(client.add_member(&owner, &child, &FamilyRole::Member, &100));
+    assert_eq!(client.get_member_count(), 1);
+}
+
+#[test]
+#[should_panic(expected = "Only Owner can remove members")]
+fn test_clear_all_members_owner_only() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let admin = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &admin, &FamilyRole::Admin, &0);
+
+    client.clear_all_members(&admin);
+}
+
+mod price_oracle {
+    use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env};
+
+    #[contract]
+    pub struct PriceOracle;
+
+    #[contractimpl]
+    impl PriceOracle {
+        pub fn set_price(env: Env, price: i128) {
+            env.storage()
+                .instance()
+                .set(&symbol_short!("PRICE"), &price);
+        }
+
+        pub fn price(env: Env, _token: Address) -> i128 {
+            env.storage()
+                .instance()
+                .get(&symbol_short!("PRICE"))
+                .unwrap_or(0)
+        }
+    }
+}
+
+#[test]
+fn test_check_spending_limit_in_ref_converts_with_oracle() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+    let oracle_id = env.register_contract(None, price_oracle::PriceOracle);
+    let oracle = price_oracle::PriceOracleClient::new(&env, &oracle_id);
+
+    let owner = Address::generate(&env);
+    let member = Address::generate(&env);
+    let token = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    // Limit of 100 reference units.
+    client.add_member(&owner, &member, &FamilyRole::Member, &100);
+    assert!(client.set_price_oracle(&owner, &Some(oracle_id.clone())));
+    assert_eq!(client.get_price_oracle(), Some(oracle_id));
+
+    // One token is worth 0.25 reference units.
+    oracle.set_price(&2_500_000);
+    assert!(client.check_spending_limit_in_ref(&member, &token, &400));
+    assert!(!client.check_spending_limit_in_ref(&member, &token, &404));
+
+    // Token doubles to 0.5: the same 400 is now worth 200.
+    oracle.set_price(&5_000_000);
+    assert!(!client.check_spending_limit_in_ref(&member, &token, &400));
+    assert!(client.check_spending_limit_in_ref(&member, &token, &200));
+
+    oracle.set_price(&0);
+    assert!(!client.check_spending_limit_in_ref(&member, &token, &1));
+}
+
+#[test]
+fn test_check_spending_limit_in_ref_without_oracle_compares_directly() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let member = Address::generate(&env);
+    let token = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &admin, &FamilyRole::Admin, &0);
+    client.add_member(&owner, &member, &FamilyRole::Member, &100);
+    assert_eq!(client.get_price_oracle(), None);
+
+    assert!(client.check_spending_limit_in_ref(&member, &token, &100));
+    assert!(!client.check_spending_limit_in_ref(&member, &token, &101));
+
+    assert_eq!(
+        client.try_set_price_oracle(&admin, &Some(Address::generate(&env))),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_get_member_addresses() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+    assert_eq!(client.get_member_addresses().len(), 0);
+
+    let owner = Address::generate(&env);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &first, &FamilyRole::Member, &100);
+    client.add_member(&owner, &second, &FamilyRole::Viewer, &0);
+
+    let addresses = client.get_member_addresses();
+    assert_eq!(addresses.len(), 3);
+    for address in [&owner, &first, &second] {
+        assert!(addresses.contains(address));
+    }
+    // Same order as the paged member listing.
+    let page = client.get_members_paged(&0, &10);
+    for (i, member) in page.iter().enumerate() {
+        assert_eq!(addresses.get(i as u32).unwrap(), member.address);
+    }
+}
+
+#[test]
+fn test_add_member_rejects_owner_address() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+
+    assert_eq!(
+        client.try_add_member(&owner, &owner, &FamilyRole::Member, &100),
+        Err(Ok(Error::InvalidRole))
+    );
+    assert_eq!(client.get_member(&owner).unwrap().role, FamilyRole::Owner);
+}
+
+#[test]
+fn test_legacy_add_paths_cannot_demote_owner() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let admin = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &admin, &FamilyRole::Admin, &0);
+
+    assert!(client
+        .try_add_family_member(&admin, &owner, &FamilyRole::Member)
+        .is_err());
+    let batch = vec![
+        &env,
+        BatchMemberItem {
+            address: owner.clone(),
+            role: FamilyRole::Viewer,
+        },
+    ];
+    assert!(client.try_batch_add_family_members(&admin, &batch).is_err());
+
+    assert_eq!(client.get_member(&owner).unwrap().role, FamilyRole::Owner);
+}
+
+#[test]
+fn test_emergency_withdraw_sweeps_full_balance() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &100);
+
+    let token_admin = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    let token_client = TokenClient::new(&env, &token);
+    StellarAssetClient::new(&env, &token).mint(&contract_id, &5_000);
+    let safe = Address::generate(&env);
+
+    client.pause(&owner);
+    assert_eq!(client.emergency_withdraw(&owner, &token, &safe), 5_000);
+    assert_eq!(token_client.balance(&safe), 5_000);
+    assert_eq!(token_client.balance(&contract_id), 0);
+
+    let topics: Vec<Val> = vec![
+        &env,
+        symbol_short!("emerg").into_val(&env),
+        EmergencyEvent::Withdrawal.into_val(&env),
+    ];
+    assert!(env
+        .events()
+        .all()
+        .iter()
+        .any(|(_, event_topics, _)| event_topics == topics));
+
+    assert_eq!(client.emergency_withdraw(&owner, &token, &safe), 0);
+}
+
+#[test]
+fn test_emergency_withdraw_owner_only() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let admin = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &admin, &FamilyRole::Admin, &0);
+
+    let token_admin = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    StellarAssetClient::new(&env, &token).mint(&contract_id, &5_000);
+
+    assert_eq!(
+        client.try_emergency_withdraw(&admin, &token, &admin),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(TokenClient::new(&env, &token).balance(&contract_id), 5_000);
+}
+
+#[test]
+fn test_member_expiry_blocks_spending_after_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let summer = Address::generate(&env);
+    let regular = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &summer, &FamilyRole::Member, &500);
+    client.add_member(&owner, &regular, &FamilyRole::Member, &500);
+
+    let expires_at = env.ledger().timestamp() + 1_000;
+    assert!(client.set_member_expiry(&owner, &summer, &expires_at));
+    assert_eq!(
+        client.get_member(&summer).unwrap().limit_expires_at,
+        Some(expires_at)
+    );
+
+    assert!(client.check_spending_limit(&summer, &100));
+    client.record_spend(&summer, &100);
+
+    let mut ledger = env.ledger().get();
+    ledger.timestamp = expires_at + 1;
+    env.ledger().set(ledger);
+
+    assert!(!client.check_spending_limit(&summer, &100));
+    assert_eq!(
+        client.evaluate_spend(&summer, &100),
+        SpendCheckResult::LimitExpired
+    );
+    assert_eq!(
+        client.try_record_spend(&summer, &100),
+        Err(Ok(Error::LimitExpired))
+    );
+    assert!(
+        !client
+            .get_resolved_member_config(&summer)
+            .unwrap()
+            .can_spend
+    );
+
+    // Members without an expiry are unaffected.
+    assert!(client.check_spending_limit(&regular, &100));
+    assert!(client.record_spend(&regular, &100));
+
+    // Clearing the expiry restores spending.
+    client.set_member_expiry(&owner, &summer, &0);
+    assert!(client.check_spending_limit(&summer, &100));
+}
+
+#[test]
+fn test_set_member_expiry_validation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &admin, &FamilyRole::Admin, &0);
+    client.add_member(&owner, &child, &FamilyRole::Member, &100);
+
+    assert_eq!(
+        client.try_set_member_expiry(&admin, &child, &10),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_member_expiry(&owner, &owner, &10),
+        Err(Ok(Error::InvalidRole))
+    );
+    assert_eq!(
+        client.try_set_member_expiry(&owner, &Address::generate(&env), &10),
+        Err(Ok(Error::MemberNotFound))
+    );
+}
+
+#[test]
+fn test_update_member_role_keeps_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &400);
+    client.record_spend(&child, &150);
+
+    assert!(client.update_member_role(&owner, &child, &FamilyRole::Admin));
+
+    let record = client.get_member(&child).unwrap();
+    assert_eq!(record.role, FamilyRole::Admin);
+    assert_eq!(record.spending_limit, 400);
+    assert_eq!(record.spent, 150);
+
+    assert!(!client.update_member_role(&owner, &Address::generate(&env), &FamilyRole::Viewer));
+}
+
+#[test]
+#[should_panic(expected = "Cannot assign Owner role")]
+fn test_update_member_role_rejects_owner_role() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &400);
+
+    client.update_member_role(&owner, &child, &FamilyRole::Owner);
+}
+
+#[test]
+#[should_panic(expected = "Only Owner can change member roles")]
+fn test_update_member_role_owner_only() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &admin, &FamilyRole::Admin, &0);
+    client.add_member(&owner, &child, &FamilyRole::Member, &400);
+
+    client.update_member_role(&admin, &child, &FamilyRole::Admin);
+}
+
+#[test]
+fn test_get_total_spent_and_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+    assert_eq!(client.get_total_spent(), 0);
+
+    let owner = Address::generate(&env);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &first, &FamilyRole::Member, &1_000);
+    client.add_member(&owner, &second, &FamilyRole::Member, &500);
+    client.record_spend(&first, &300);
+    client.record_spend(&second, &200);
+    client.record_spend(&owner, &50);
+
+    assert_eq!(client.get_total_spent(), 550);
+    assert_eq!(client.get_total_limit(), 1_500);
+    assert_eq!(client.get_summary().total_limit, 1_500);
+}
+
+#[test]
+fn test_totals_saturate_on_extreme_values() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &first, &FamilyRole::Member, &i128::MAX);
+    client.add_member(&owner, &second, &FamilyRole::Member, &i128::MAX);
+    client.record_spend(&first, &i128::MAX);
+    client.record_spend(&second, &i128::MAX);
+
+    assert_eq!(client.get_total_spent(), i128::MAX);
+    assert_eq!(client.get_total_limit(), i128::MAX);
+    assert_eq!(client.get_summary().total_limit, i128::MAX);
+}
+
+#[test]
+fn test_init_rejected_after_instance_data_loss() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    assert_eq!(client.get_init_generation(), 1);
+    assert_eq!(
+        client.try_init(&Address::generate(&env), &vec![&env]).err(),
+        Some(Ok(Error::AlreadyInitialized.into()))
+    );
+
+    // Instance data is gone, but the persistent generation marker remains.
+    env.as_contract(&contract_id, || {
+        env.storage().instance().remove(&symbol_short!("OWNER"));
+        env.storage().instance().remove(&symbol_short!("MEMBERS"));
+    });
+    let attacker = Address::generate(&env);
+    assert_eq!(
+        client.try_init(&attacker, &vec![&env]).err(),
+        Some(Ok(Error::AlreadyInitialized.into()))
+    );
+    assert_eq!(
+        client.try_initialize_with_members(&attacker, &vec![&env]),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+}
+
+#[test]
+fn test_force_reinitialize_resets_wallet() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let child = Address::generate(&env);
+    let guardian = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &admin, &FamilyRole::Admin, &0);
+    client.add_member(&owner, &guardian, &FamilyRole::Guardian, &0);
+    client.add_member(&owner, &child, &FamilyRole::Member, &100);
+    client.set_wallet_name(&owner, &String::from_str(&env, "Old"));
+    client.set_limit_bounds(&owner, &10, &1_000);
+    client.flag_dispute(&guardian);
+
+    assert_eq!(
+        client.try_force_reinitialize(&admin),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    assert!(client.force_reinitialize(&owner));
+    assert_eq!(client.get_init_generation(), 2);
+    assert_eq!(client.get_owner(), owner);
+    assert_eq!(client.get_member_count(), 0);
+    assert!(client.get_member(&child).is_none());
+    assert_eq!(client.get_wallet_name(), None);
+    assert!(client.get_limit_bounds().is_none());
+    assert_eq!(client.get_dispute_until(), None);
+
+    assert!(client.add_member(&owner, &child, &FamilyRole::Member, &5));
+}
+
+#[test]
+fn test_max_tx_count_limits_spends_per_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &1_000);
+    client.set_limit_period(&owner, &child, &LimitPeriod::Daily);
+    assert!(client.set_max_tx_count(&owner, &child, &2));
+
+    assert_eq!(client.get_tx_count(&child), Some(0));
+    client.record_spend(&child, &10);
+    client.record_spend(&child, &10);
+    assert_eq!(client.get_tx_count(&child), Some(2));
+    assert_eq!(
+        client.try_record_spend(&child, &10),
+        Err(Ok(Error::TxCountExceeded))
+    );
+    assert!(!client.spend(&child, &10));
+    assert_eq!(client.get_tx_count(&child), Some(2));
+
+    // A new window starts a fresh count.
+    let mut ledger = env.ledger().get();
+    ledger.timestamp += 86_400;
+    env.ledger().set(ledger);
+    assert_eq!(client.get_tx_count(&child), Some(0));
+    assert!(client.record_spend(&child, &10));
+    assert_eq!(client.get_tx_count(&child), Some(1));
+
+    assert_eq!(client.get_tx_count(&Address::generate(&env)), None);
+}
+
+#[test]
+fn test_reset_spent_clears_tx_count() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &1_000);
+    client.set_max_tx_count(&owner, &child, &1);
+    client.record_spend(&child, &10);
+    assert_eq!(
+        client.try_record_spend(&child, &10),
+        Err(Ok(Error::TxCountExceeded))
+    );
+
+    client.reset_spent(&owner, &child);
+    assert_eq!(client.get_tx_count(&child), Some(0));
+    assert!(client.record_spend(&child, &10));
+}
+
+#[test]
+fn test_get_members_sorted_by_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let small = Address::generate(&env);
+    let large = Address::generate(&env);
+    let medium = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &small, &FamilyRole::Member, &100);
+    client.add_member(&owner, &large, &FamilyRole::Member, &900);
+    client.add_member(&owner, &medium, &FamilyRole::Member, &500);
+
+    let ascending = client.get_members_sorted_by_limit(&false);
+    assert_eq!(ascending.len(), 4);
+    let limits: Vec<i128> = vec![&env, 0, 100, 500, 900];
+    for (member, limit) in ascending.iter().zip(limits.iter()) {
+        assert_eq!(member.spending_limit, limit);
+    }
+    assert_eq!(ascending.get(0).unwrap().address, owner);
+    assert_eq!(ascending.get(3).unwrap().address, large);
+
+    let descending = client.get_members_sorted_by_limit(&true);
+    assert_eq!(descending.len(), 4);
+    let limits: Vec<i128> = vec![&env, 900, 500, 100, 0];
+    for (member, limit) in descending.iter().zip(limits.iter()) {
+        assert_eq!(member.spending_limit, limit);
+    }
+    assert_eq!(descending.get(0).unwrap().address, large);
+}
+
+#[test]
+fn test_get_members_sorted_by_limit_ties_follow_map_order() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    for _ in 0..3 {
+        client.add_member(&owner, &Address::generate(&env), &FamilyRole::Member, &250);
+    }
+
+    // Same-limit members appear in address order, as in get_member_addresses.
+    let order = client.get_member_addresses();
+    let tied = |sorted: Vec<FamilyMember>| {
+        let mut addresses = Vec::<Address>::new(&env);
+        for member in sorted.iter() {
+            if member.spending_limit == 250 {
+                addresses.push_back(member.address);
+            }
+        }
+        addresses
+    };
+    let mut expected = Vec::<Address>::new(&env);
+    for address in order.iter() {
+        if address != owner {
+            expected.push_back(address);
+        }
+    }
+    assert_eq!(tied(client.get_members_sorted_by_limit(&false)), expected);
+    assert_eq!(tied(client.get_members_sorted_by_limit(&true)), expected);
+}
+
+#[test]
+fn test_record_deposit_tracks_totals_and_emits_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let sender = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+
+    let token_admin = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    let token_client = TokenClient::new(&env, &token);
+    StellarAssetClient::new(&env, &token).mint(&sender, &1_000);
+
+    assert_eq!(client.record_deposit(&sender, &token, &600, &true), 600);
+    let (_, _, data) = env
+        .events()
+        .all()
+        .iter()
+        .find(|(_, topics, _)| {
+            *topics
+                == vec![
+                    &env,
+                    symbol_short!("deposit").into_val(&env),
+                    symbol_short!("received").into_val(&env),
+                    sender.into_val(&env),
+                ]
+        })
+        .expect("deposit event not published");
+    let event: DepositReceivedEvent = data.into_val(&env);
+    assert_eq!(event.from, sender);
+    assert_eq!(event.token, token);
+    assert_eq!(event.amount, 600);
+
+    assert_eq!(client.record_deposit(&sender, &token, &400, &true), 1_000);
+    assert_eq!(client.get_total_deposited(&token), 1_000);
+    assert_eq!(token_client.balance(&contract_id), 1_000);
+    assert_eq!(token_client.balance(&sender), 0);
+
+    assert_eq!(
+        client.try_record_deposit(&sender, &token, &0, &true),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(client.get_total_deposited(&Address::generate(&env)), 0);
+}
+
+#[test]
+fn test_record_deposit_without_pull_records_direct_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let sender = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+
+    let token_admin = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    let token_client = TokenClient::new(&env, &token);
+    StellarAssetClient::new(&env, &token).mint(&sender, &1_000);
+
+    // The remittance arrives by a plain token transfer.
+    token_client.transfer(&sender, &contract_id, &300);
+
+    assert_eq!(
+        client.try_record_deposit(&sender, &token, &300, &false),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(client.record_deposit(&owner, &token, &300, &false), 300);
+    assert_eq!(client.get_total_deposited(&token), 300);
+    assert_eq!(token_client.balance(&contract_id), 300);
+    assert_eq!(token_client.balance(&sender), 700);
+}
+
+#[test]
+fn test_get_version_defaults_and_tracks_upgrades() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    assert_eq!(client.get_version(), CONTRACT_VERSION);
+
+    client.set_version(&owner, &(CONTRACT_VERSION + 1));
+    assert_eq!(client.get_version(), CONTRACT_VERSION + 1);
+}
+
+#[test]
+fn test_dispute_window_blocks_limit_changes_until_it_elapses() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let guardian = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &guardian, &FamilyRole::Guardian, &0);
+    client.add_member(&owner, &member, &FamilyRole::Member, &1_000);
+
+    assert_eq!(
+        client.try_flag_dispute(&member),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    let until = client.flag_dispute(&guardian);
+    assert_eq!(until, env.ledger().timestamp() + 3 * 86400);
+    assert_eq!(client.get_dispute_until(), Some(until));
+
+    assert_eq!(
+        client.try_update_spending_limit(&owner, &member, &2_000, &None),
+        Err(Ok(Error::DisputeActive))
+    );
+    assert_eq!(
+        client.try_add_member(&owner, &Address::generate(&env), &FamilyRole::Member, &500),
+        Err(Ok(Error::DisputeActive))
+    );
+
+    let mut ledger = env.ledger().get();
+    ledger.timestamp = until;
+    env.ledger().set(ledger);
+
+    assert_eq!(client.get_dispute_until(), None);
+    assert!(client.update_spending_limit(&owner, &member, &2_000, &None));
+    assert_eq!(client.get_member(&member).unwrap().spending_limit, 2_000);
+}
+
+#[test]
+fn test_owner_can_resolve_dispute_early() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let guardian = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &guardian, &FamilyRole::Guardian, &0);
+    client.add_member(&owner, &member, &FamilyRole::Member, &1_000);
+
+    client.flag_dispute(&guardian);
+    assert_eq!(
+        client.try_resolve_dispute(&guardian),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    assert!(client.resolve_dispute(&owner));
+    assert_eq!(client.get_dispute_until(), None);
+    assert!(client.update_spending_limit(&owner, &member, &2_000, &None));
+}
+
+#[test]
+fn test_update_member_name_keeps_role_and_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &250);
+    assert_eq!(client.get_member(&child).unwrap().name, None);
+
+    let name = String::from_str(&env, "Alex");
+    assert!(client.update_member_name(&owner, &child, &name));
+    let record = client.get_member(&child).unwrap();
+    assert_eq!(record.name, Some(name.clone()));
+    assert_eq!(record.role, FamilyRole::Member);
+    assert_eq!(record.spending_limit, 250);
+
+    assert!(!client.update_member_name(&owner, &Address::generate(&env), &name));
+    assert!(client
+        .try_update_member_name(&child, &child, &String::from_str(&env, "Me"))
+        .is_err());
+}
+
+#[test]
+#[should_panic(expected = "Name cannot be empty")]
+fn test_update_member_name_rejects_empty_name() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &250);
+
+    client.update_member_name(&owner, &child, &String::from_str(&env, ""));
+}
+
+#[test]
+fn test_enforce_unique_names_rejects_duplicates_only_when_on() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &first, &FamilyRole::Member, &100);
+    client.add_member(&owner, &second, &FamilyRole::Member, &100);
+    let alex = String::from_str(&env, "Alex");
+
+    // Off by default: two members may share a name.
+    assert!(!client.get_enforce_unique_names());
+    assert!(client.update_member_name(&owner, &first, &alex));
+    assert!(client.update_member_name(&owner, &second, &alex));
+
+    assert_eq!(
+        client.try_set_enforce_unique_names(&first, &true),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.set_enforce_unique_names(&owner, &true);
+    assert!(client.get_enforce_unique_names());
+
+    let sam = String::from_str(&env, "Sam");
+    assert!(client.update_member_name(&owner, &second, &sam));
+    assert!(client.try_update_member_name(&owner, &first, &sam).is_err());
+    assert_eq!(client.get_member(&first).unwrap().name, Some(alex));
+
+    // Re-saving a member's own name is not a collision.
+    assert!(client.update_member_name(&owner, &second, &sam));
+}
+
+#[test]
+fn test_member_metadata_set_overwrite_and_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &member, &FamilyRole::Member, &1_000);
+
+    let school = String::from_str(&env, "school");
+    let phone = String::from_str(&env, "phone");
+    client.set_member_metadata(
+        &owner,
+        &member,
+        &school,
+        &String::from_str(&env, "Lincoln High"),
+    );
+    client.set_member_metadata(&owner, &member, &phone, &String::from_str(&env, "555-0100"));
+    assert_eq!(
+        client.get_member_metadata(&member, &school),
+        Some(String::from_str(&env, "Lincoln High"))
+    );
+    assert_eq!(
+        client.get_member_metadata(&member, &phone),
+        Some(String::from_str(&env, "555-0100"))
+    );
+
+    client.set_member_metadata(
+        &owner,
+        &member,
+        &school,
+        &String::from_str(&env, "Roosevelt"),
+    );
+    assert_eq!(
+        client.get_member_metadata(&member, &school),
+        Some(String::from_str(&env, "Roosevelt"))
+    );
+
+    client.set_member_metadata(&owner, &member, &phone, &String::from_str(&env, ""));
+    assert_eq!(client.get_member_metadata(&member, &phone), None);
+
+    assert_eq!(
+        client.try_set_member_metadata(&member, &member, &phone, &String::from_str(&env, "x")),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_member_metadata(
+            &owner,
+            &Address::generate(&env),
+            &phone,
+            &String::from_str(&env, "x")
+        ),
+        Err(Ok(Error::MemberNotFound))
+    );
+
+    let keys = ["k1", "k2", "k3", "k4", "k5", "k6", "k7", "k8", "k9", "k10"];
+    for key in keys.iter().take(9) {
+        client.set_member_metadata(&owner, &member, &String::from_str(&env, key), &school);
+    }
+    assert_eq!(
+        client.try_set_member_metadata(&owner, &member, &String::from_str(&env, keys[9]), &school),
+        Err(Ok(Error::MetadataLimitExceeded))
+    );
+    // Overwriting an existing key is still allowed at the cap.
+    client.set_member_metadata(&owner, &member, &school, &phone);
+    assert_eq!(client.get_member_metadata(&member, &school), Some(phone));
+}
+
+#[test]
+fn test_update_limits_by_role_only_touches_target_role() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let child_a = Address::generate(&env);
+    let child_b = Address::generate(&env);
+    let viewer = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &admin, &FamilyRole::Admin, &5_000);
+    client.add_member(&owner, &child_a, &FamilyRole::Member, &100);
+    client.add_member(&owner, &child_b, &FamilyRole::Member, &200);
+    client.add_member(&owner, &viewer, &FamilyRole::Viewer, &50);
+
+    assert_eq!(
+        client.update_limits_by_role(&owner, &FamilyRole::Member, &750),
+        2
+    );
+    assert_eq!(client.get_member(&child_a).unwrap().spending_limit, 750);
+    assert_eq!(client.get_member(&child_b).unwrap().spending_limit, 750);
+    assert_eq!(client.get_member(&admin).unwrap().spending_limit, 5_000);
+    assert_eq!(client.get_member(&viewer).unwrap().spending_limit, 50);
+
+    assert_eq!(
+        client.update_limits_by_role(&owner, &FamilyRole::Guardian, &750),
+        0
+    );
+    assert_eq!(
+        client.try_update_limits_by_role(&owner, &FamilyRole::Member, &0),
+        Err(Ok(Error::InvalidSpendingLimit))
+    );
+    assert_eq!(
+        client.try_update_limits_by_role(&owner, &FamilyRole::Owner, &750),
+        Err(Ok(Error::InvalidRole))
+    );
+    assert_eq!(
+        client.try_update_limits_by_role(&admin, &FamilyRole::Member, &750),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_simulate_spend_returns_remainder_without_writing() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &member, &FamilyRole::Member, &1_000);
+    client.record_spend(&member, &300);
+
+    assert_eq!(client.simulate_spend(&member, &500), Some(200));
+    assert_eq!(client.simulate_spend(&member, &700), Some(0));
+    assert_eq!(client.get_member(&member).unwrap().spent_in_period, 300);
+    assert_eq!(client.get_remaining_limit(&member), Some(700));
+
+    assert_eq!(client.simulate_spend(&member, &701), None);
+    assert_eq!(client.simulate_spend(&member, &0), None);
+    assert_eq!(client.simulate_spend(&Address::generate(&env), &10), None);
+    assert_eq!(client.simulate_spend(&owner, &1_000_000), Some(i128::MAX));
+}
+
+#[test]
+fn test_mutating_call_emits_ttl_extended_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set(LedgerInfo {
+        protocol_version: 20,
+        sequence_number: 100,
+        timestamp: 1000,
+        network_id: [0; 32],
+        base_reserve: 10,
+        min_temp_entry_ttl: 100,
+        min_persistent_entry_ttl: 100,
+        max_entry_ttl: 700_000,
+    });
+
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.set_ttl_config(&owner, &600_000, &650_000);
+
+    client.add_member(&owner, &Address::generate(&env), &FamilyRole::Member, &0);
+
+    let topics: Vec<Val> = vec![
+        &env,
+        symbol_short!("family").into_val(&env),
+        FamilyWalletEvent::TtlExtended.into_val(&env),
+    ];
+    let (_, _, data) = env
+        .events()
+        .all()
+        .iter()
+        .rev()
+        .find(|(event_contract, event_topics, _)| {
+            *event_contract == contract_id && *event_topics == topics
+        })
+        .expect("TTL event not published");
+    let event: TtlExtendedEvent = data.into_val(&env);
+    assert_eq!(event.bump_amount, 650_000);
+    assert_eq!(event.ledger, 100);
+}
+
+#[test]
+fn test_get_members_added_after_filters_and_keeps_original_timestamp() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let mut ledger = env.ledger().get();
+    ledger.timestamp = 1_000;
+    env.ledger().set(ledger.clone());
+
+    let owner = Address::generate(&env);
+    let early = Address::generate(&env);
+    let late = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &early, &FamilyRole::Member, &100);
+
+    ledger.timestamp = 2_000;
+    env.ledger().set(ledger.clone());
+    client.add_member(&owner, &late, &FamilyRole::Member, &100);
+
+    let added = client.get_members_added_after(&1_500);
+    assert_eq!(added.len(), 1);
+    assert_eq!(added.get(0).unwrap().address, late);
+    assert_eq!(added.get(0).unwrap().added_at, 2_000);
+    assert_eq!(client.get_members_added_after(&0).len(), 3);
+    assert_eq!(client.get_members_added_after(&2_000).len(), 0);
+
+    // Re-adding an existing member overwrites it but keeps `added_at`.
+    ledger.timestamp = 3_000;
+    env.ledger().set(ledger);
+    client.add_family_member(&owner, &early, &FamilyRole::Viewer);
+    let record = client.get_member(&early).unwrap();
+    assert_eq!(record.role, FamilyRole::Viewer);
+    assert_eq!(record.added_at, 1_000);
+    assert_eq!(client.get_members_added_after(&1_500).len(), 1);
+}
+
+#[test]
+fn test_quick_add_member_uses_configured_defaults() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &admin, &FamilyRole::Admin, &0);
+
+    assert_eq!(
+        client.try_quick_add_member(&owner, &child),
+        Err(Ok(Error::DefaultsNotSet))
+    );
+    assert!(client.get_member(&child).is_none());
+
+    assert_eq!(
+        client.try_set_defaults(&owner, &FamilyRole::Owner, &100),
+        Err(Ok(Error::InvalidRole))
+    );
+    assert_eq!(
+        client.try_set_defaults(&owner, &FamilyRole::Member, &-1),
+        Err(Ok(Error::InvalidSpendingLimit))
+    );
+    assert_eq!(
+        client.try_set_defaults(&admin, &FamilyRole::Member, &100),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    client.set_defaults(&owner, &FamilyRole::Member, &250);
+    assert!(client.quick_add_member(&owner, &child));
+    let record = client.get_member(&child).unwrap();
+    assert_eq!(record.role, FamilyRole::Member);
+    assert_eq!(record.spending_limit, 250);
+
+    // Bounds set after the defaults still apply at quick-add time.
+    client.set_limit_bounds(&owner, &500, &1_000);
+    assert_eq!(
+        client.try_quick_add_member(&admin, &Address::generate(&env)),
+        Err(Ok(Error::LimitOutOfBounds))
+    );
+}
+
+#[test]
+fn test_delegate_spends_share_member_limit_until_revoked() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    let device = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &1_000);
+
+    assert_eq!(
+        client.try_record_spend_as_delegate(&device, &child, &100),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_add_delegate(&child, &child, &device),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_add_delegate(&owner, &child, &child),
+        Err(Ok(Error::InvalidRole))
+    );
+
+    client.add_delegate(&owner, &child, &device);
+    assert_eq!(client.get_delegates(&child), vec![&env, device.clone()]);
+
+    assert!(client.record_spend_as_delegate(&device, &child, &600));
+    client.record_spend(&child, &300);
+    assert_eq!(client.get_member(&child).unwrap().spent_in_period, 900);
+    assert_eq!(
+        client.try_record_spend_as_delegate(&device, &child, &200),
+        Err(Ok(Error::SpendingLimitExceeded))
+    );
+
+    assert!(client.remove_delegate(&owner, &child, &device));
+    assert!(!client.remove_delegate(&owner, &child, &device));
+    assert_eq!(client.get_delegates(&child).len(), 0);
+    assert_eq!(
+        client.try_record_spend_as_delegate(&device, &child, &50),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_delegate_can_sign_real_spend_paths() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    let device = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &1_000);
+
+    let token_admin = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    let token_client = TokenClient::new(&env, &token);
+    StellarAssetClient::new(&env, &token).mint(&contract_id, &1_000);
+    client.set_token_limit(&owner, &child, &token, &0);
+    let shop = Address::generate(&env);
+
+    assert_eq!(
+        client.try_spend_token_as_delegate(&device, &child, &token, &100, &shop),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert!(client.try_spend_as_delegate(&device, &child, &100).is_err());
+
+    client.add_delegate(&owner, &child, &device);
+    assert!(client.spend_as_delegate(&device, &child, &100));
+    assert!(client.spend_token_as_delegate(&device, &child, &token, &200, &shop));
+    assert_eq!(token_client.balance(&shop), 200);
+
+    client.set_withdrawal_delay(&owner, &60);
+    let id = client.request_withdrawal(&child, &300);
+    env.ledger().with_mut(|li| li.timestamp += 60);
+    assert!(client.execute_withdrawal_as_delegate(&device, &child, &id));
+    assert_eq!(client.get_member(&child).unwrap().spent_in_period, 600);
+
+    // Removing the member drops its delegates; a re-add starts clean.
+    client.remove_family_member(&owner, &child);
+    assert_eq!(client.get_delegates(&child).len(), 0);
+    client.add_member(&owner, &child, &FamilyRole::Member, &1_000);
+    assert_eq!(
+        client.try_record_spend_as_delegate(&device, &child, &10),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    client.add_delegate(&owner, &child, &device);
+    client.clear_all_members(&owner);
+    assert_eq!(client.get_delegates(&child).len(), 0);
+}
+
+#[test]
+fn test_export_then_import_members_round_trip() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let source_id = env.register_contract(None, FamilyWallet);
+    let source = FamilyWalletClient::new(&env, &source_id);
+
+    let owner = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let child = Address::generate(&env);
+    source.init(&owner, &vec![&env]);
+    source.add_member(&owner, &admin, &FamilyRole::Admin, &0);
+    source.add_member(&owner, &child, &FamilyRole::Member, &1_000);
+    source.set_limit_period(&owner, &child, &LimitPeriod::Weekly);
+    source.set_max_tx_count(&owner, &child, &5);
+    source.record_spend(&child, &400);
+
+    assert!(matches!(
+        source.try_export_members(&admin),
+        Err(Ok(Error::Unauthorized))
+    ));
+    let exported = source.export_members(&owner);
+    assert_eq!(exported.len(), 3);
+
+    let target_id = env.register_contract(None, FamilyWallet);
+    let target = FamilyWalletClient::new(&env, &target_id);
+    target.init(&owner, &vec![&env]);
+    assert_eq!(target.import_members(&owner, &exported), 2);
+
+    for address in [admin.clone(), child.clone()] {
+        let before = source.get_member(&address).unwrap();
+        let after = target.get_member(&address).unwrap();
+        assert_eq!(after.role, before.role);
+        assert_eq!(after.spending_limit, before.spending_limit);
+        assert_eq!(after.added_at, before.added_at);
+        assert_eq!(after.limit_period, before.limit_period);
+        assert_eq!(after.period_start, before.period_start);
+        assert_eq!(after.spent_in_period, before.spent_in_period);
+        assert_eq!(after.spent, before.spent);
+        assert_eq!(after.tx_count, before.tx_count);
+        assert_eq!(after.max_tx_count, before.max_tx_count);
+        assert_eq!(after.active, before.active);
+    }
+    assert_eq!(target.get_member(&child).unwrap().spent, 400);
+
+    // Importing the same records again collides with the restored ones.
+    assert_eq!(
+        target.try_import_members(&owner, &exported),
+        Err(Ok(Error::InvalidRole))
+    );
+}
+
+#[test]
+fn test_verify_and_repair_integrity_after_desync() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &1_000);
+    assert!(client.verify_integrity());
+    assert_eq!(client.repair_integrity(&owner), 0);
+
+    // Desync storage directly: drop the owner record, list a co-owner
+    // with no record, and file the child's record under another key.
+    let ghost = Address::generate(&env);
+    let stray = Address::generate(&env);
+    env.as_contract(&contract_id, || {
+        let mut members: Map<Address, FamilyMember> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MEMBERS"))
+            .unwrap();
+        members.remove(owner.clone());
+        let record = members.get(child.clone()).unwrap();
+        members.set(stray.clone(), record);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MEMBERS"), &members);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("ADMINS"), &vec![&env, ghost.clone()]);
+    });
+    assert!(!client.verify_integrity());
+
+    assert_eq!(
+        client.try_repair_integrity(&child),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(client.repair_integrity(&owner), 3);
+    assert!(client.verify_integrity());
+
+    assert_eq!(client.get_member(&owner).unwrap().role, FamilyRole::Owner);
+    assert_eq!(client.get_member(&stray).unwrap().address, stray);
+    assert_eq!(client.get_admins().len(), 0);
+    assert_eq!(client.get_storage_stats().total_members, 3);
+}
+
+#[test]
+fn test_first_time_recipient_requires_owner_approval() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &1_000);
+    client.set_recipient_approval(&owner, &true);
+
+    let token_admin = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    let token_client = TokenClient::new(&env, &token);
+    StellarAssetClient::new(&env, &token).mint(&contract_id, &1_000);
+    let shop = Address::generate(&env);
+    client.set_token_limit(&owner, &child, &token, &0);
+
+    // The first payment to a new recipient is held, with nothing moved.
+    assert!(!client.spend_token(&child, &token, &100, &shop));
+    assert_eq!(token_client.balance(&shop), 0);
+    assert_eq!(client.get_member(&child).unwrap().spent_in_period, 0);
+    assert_eq!(
+        client.get_pending_recipients(&child),
+        vec![&env, shop.clone()]
+    );
+
+    assert_eq!(
+        client.try_approve_recipient(&child, &child, &shop),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.approve_recipient(&owner, &child, &shop);
+    assert!(client.is_known_recipient(&child, &shop));
+    assert_eq!(client.get_pending_recipients(&child).len(), 0);
+
+    // Approved and then known recipients need no further approval.
+    assert!(client.spend_token(&child, &token, &100, &shop));
+    assert!(client.spend_token(&child, &token, &50, &shop));
+    assert_eq!(token_client.balance(&shop), 150);
+    assert_eq!(client.get_member(&child).unwrap().spent_in_period, 150);
+}
+
+#[test]
+fn test_held_recipient_payment_leaves_wallet_counters_untouched() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &1_000);
+    client.set_wallet_monthly_cap(&owner, &5_000, &0);
+    client.set_wallet_rate_limit(&owner, &3, &86_400);
+    client.set_recipient_approval(&owner, &true);
+
+    let token_admin = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    StellarAssetClient::new(&env, &token).mint(&contract_id, &1_000);
+    let shop = Address::generate(&env);
+    client.set_token_limit(&owner, &child, &token, &0);
+
+    assert!(!client.spend_token(&child, &token, &400, &shop));
+    assert_eq!(client.get_wallet_monthly_cap().unwrap().spent, 0);
+    assert_eq!(client.get_wallet_monthly_remaining(), Some(5_000));
+    assert_eq!(client.get_wallet_rate_limit().unwrap().count, 0);
+
+    client.approve_recipient(&owner, &child, &shop);
+    assert!(client.spend_token(&child, &token, &400, &shop));
+    assert_eq!(client.get_wallet_monthly_cap().unwrap().spent, 400);
+    assert_eq!(client.get_wallet_rate_limit().unwrap().count, 1);
+}
+
+#[test]
+fn test_limit_update_memo_in_history_and_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &100);
+
+    let memo = String::from_str(&env, "birthday bonus");
+    client.update_limit_with_reason(&owner, &child, &300, &memo);
+
+    let (_, _, data) = env
+        .events()
+        .all()
+        .iter()
+        .rev()
+        .find(|(_, topics, _)| {
+            *topics
+                == vec![
+                    &env,
+                    symbol_short!("updated").into_val(&env),
+                    symbol_short!("limit").into_val(&env),
+                    child.into_val(&env),
+                ]
+        })
+        .expect("limit update event not published");
+    let event: SpendingLimitUpdatedEvent = data.into_val(&env);
+    assert_eq!(event.new_limit, 300);
+    assert_eq!(event.memo, Some(memo.clone()));
+
+    client.update_spending_limit(&owner, &child, &400, &None);
+    let allowance = String::from_str(&env, "weekly allowance");
+    client.update_spending_limit(&owner, &child, &450, &Some(allowance.clone()));
+    client.update_spending_limit(&owner, &child, &400, &Some(String::from_str(&env, "")));
+    let history = client.get_limit_history(&child);
+    assert_eq!(history.get(0).unwrap().memo, Some(memo));
+    assert_eq!(history.get(1).unwrap().memo, None);
+    assert_eq!(history.get(2).unwrap().memo, Some(allowance));
+    assert_eq!(history.get(3).unwrap().memo, None);
+
+    let too_long = String::from_str(
+        &env,
+        "a memo that keeps going well past the sixty-four byte bound on notes",
+    );
+    assert_eq!(
+        client.try_update_limit_with_reason(&owner, &child, &500, &too_long),
+        Err(Ok(Error::MemoTooLong))
+    );
+    assert_eq!(
+        client.try_update_spending_limit(&owner, &child, &500, &Some(too_long)),
+        Err(Ok(Error::MemoTooLong))
+    );
+    assert_eq!(client.get_member(&child).unwrap().spending_limit, 400);
+}
+
+#[test]
+fn test_is_owner_for_owner_co_owner_and_others() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let co_owner = Address::generate(&env);
+    let member = Address::generate(&env);
+    assert!(!client.is_owner(&owner));
+
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &co_owner, &FamilyRole::Admin, &0);
+    client.add_member(&owner, &member, &FamilyRole::Member, &100);
+    assert!(client.is_owner(&owner));
+    assert!(!client.is_owner(&co_owner));
+    assert!(!client.is_owner(&member));
+    assert!(!client.is_owner(&Address::generate(&env)));
+
+    client.add_admin(&owner, &co_owner);
+    assert!(client.is_owner(&co_owner));
+}
+
+#[test]
+fn test_effective_limit_grows_at_year_boundaries() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let year = 365 * 86_400u64;
+    let birth = 1_000u64;
+    let mut ledger = env.ledger().get();
+    ledger.timestamp = birth + 10 * year;
+    env.ledger().set(ledger.clone());
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &100);
+    assert_eq!(client.get_effective_limit(&child), Some(100));
+
+    assert_eq!(
+        client.try_set_age_scaling(&owner, &child, &birth, &-1),
+        Err(Ok(Error::InvalidSpendingLimit))
+    );
+    client.set_age_scaling(&owner, &child, &birth, &20);
+    assert_eq!(client.get_effective_limit(&child), Some(300));
+    assert_eq!(client.get_member(&child).unwrap().spending_limit, 100);
+
+    ledger.timestamp = birth + 11 * year - 1;
+    env.ledger().set(ledger.clone());
+    assert_eq!(client.get_effective_limit(&child), Some(300));
+
+    ledger.timestamp = birth + 11 * year;
+    env.ledger().set(ledger);
+    assert_eq!(client.get_effective_limit(&child), Some(320));
+
+    client.set_age_scaling(&owner, &child, &0, &20);
+    assert_eq!(client.get_effective_limit(&child), Some(100));
+    assert_eq!(client.get_effective_limit(&Address::generate(&env)), None);
+}
+
+#[test]
+fn test_age_scaled_limit_is_enforced_after_year_boundary() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let year = 365 * 86_400u64;
+    let birth = 1_000u64;
+    let mut ledger = env.ledger().get();
+    ledger.timestamp = birth + 10 * year - 1;
+    env.ledger().set(ledger.clone());
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &100);
+    client.set_limit_period(&owner, &child, &LimitPeriod::Daily);
+    client.set_age_scaling(&owner, &child, &birth, &20);
+
+    // Nine full years: 100 + 9 * 20.
+    assert!(client.check_spending_limit(&child, &280));
+    assert!(!client.check_spending_limit(&child, &290));
+    assert_eq!(
+        client.try_record_spend(&child, &290),
+        Err(Ok(Error::SpendingLimitExceeded))
+    );
+
+    ledger.timestamp = birth + 10 * year;
+    env.ledger().set(ledger);
+    assert!(client.check_spending_limit(&child, &290));
+    assert!(client.record_spend(&child, &290));
+    assert_eq!(client.get_remaining_limit(&child), Some(10));
+    assert_eq!(client.simulate_spend(&child, &10), Some(0));
+    assert_eq!(client.get_member(&child).unwrap().spending_limit, 100);
+}
+
+#[test]
+fn test_check_spending_limits_batch_preserves_order() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    client.init(&owner, &vec![&env]);
+    client.add_member(&owner, &child, &FamilyRole::Member, &100);
+
+    let checks = vec![
+        &env,
+        (child.clone(), 100i128),
+        (child.clone(), 101i128),
+        (stranger.clone(), 1i128),
+        (owner.clone(), 1_000_000i128),
+        (child.clone(), -5i128),
+    ];
+    let results = client.check_spending_limits_batch(&checks);
+    assert_eq!(results, vec![&env, true, false, false, true, false]);
+    for (index, (member, amount)) in checks.iter().enumerate() {
+        assert_eq!(
+            results.get(index as u32).unwrap(),
+            client.check_spending_limit(&member, &amount)
+        );
+    }
+
+    assert_eq!(client.check_spending_limits_batch(&Vec::new(&env)).len(), 0);
+}